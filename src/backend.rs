@@ -0,0 +1,308 @@
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::time::{Duration, Instant};
+
+use crossterm::{
+    style::{Print, Stylize},
+    terminal, QueueableCommand,
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// How a piece of text is displayed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Style {
+    #[default]
+    Plain,
+    Placeholder,
+    Error,
+    /// The option under the cursor of a list.
+    Selected,
+}
+
+/// A terminal that forms are drawn on.
+pub trait Backend {
+    /// Returns the size of the terminal as (columns, rows).
+    fn size(&mut self) -> io::Result<(u16, u16)>;
+    fn cursor_position(&mut self) -> io::Result<(u16, u16)>;
+    fn move_to(&mut self, x: u16, y: u16) -> io::Result<()>;
+    /// Prints text at the cursor, moving to the start of the next line on `\n`.
+    fn print(&mut self, text: &str, style: Style) -> io::Result<()>;
+    fn flush(&mut self) -> io::Result<()>;
+    fn enable_raw_mode(&mut self) -> io::Result<()>;
+    fn disable_raw_mode(&mut self) -> io::Result<()>;
+}
+
+/// Draws on a real terminal through crossterm.
+pub struct CrosstermBackend<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> CrosstermBackend<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+}
+
+impl<W: Write> Backend for CrosstermBackend<W> {
+    fn size(&mut self) -> io::Result<(u16, u16)> {
+        terminal::size()
+    }
+
+    #[cfg(unix)]
+    fn cursor_position(&mut self) -> io::Result<(u16, u16)> {
+        // crossterm asks for the position on stderr, which may not be the terminal the
+        // box is drawn on, so ask through the writer and read the reply from the terminal.
+        let raw = terminal::is_raw_mode_enabled()?;
+        if !raw {
+            terminal::enable_raw_mode()?;
+        }
+        let position = self
+            .writer
+            .write_all(b"\x1b[6n")
+            .and_then(|()| self.writer.flush())
+            .and_then(|()| read_position(Duration::from_secs(2)));
+        if !raw {
+            terminal::disable_raw_mode()?;
+        }
+        position
+    }
+
+    #[cfg(not(unix))]
+    fn cursor_position(&mut self) -> io::Result<(u16, u16)> {
+        self.writer.flush()?;
+        crossterm::cursor::position()
+    }
+
+    fn move_to(&mut self, x: u16, y: u16) -> io::Result<()> {
+        self.writer.queue(crossterm::cursor::MoveTo(x, y))?;
+        Ok(())
+    }
+
+    fn print(&mut self, text: &str, style: Style) -> io::Result<()> {
+        match style {
+            Style::Plain => self.writer.queue(Print(text))?,
+            Style::Placeholder => self.writer.queue(Print(text.dim()))?,
+            Style::Error => self.writer.queue(Print(text.red()))?,
+            Style::Selected => self.writer.queue(Print(text.reverse()))?,
+        };
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    fn enable_raw_mode(&mut self) -> io::Result<()> {
+        terminal::enable_raw_mode()
+    }
+
+    fn disable_raw_mode(&mut self) -> io::Result<()> {
+        terminal::disable_raw_mode()
+    }
+}
+
+/// Reads the reply to a cursor position query, `ESC [ row ; column R`, from the terminal.
+#[cfg(unix)]
+fn read_position(timeout: Duration) -> io::Result<(u16, u16)> {
+    use std::{fs::File, io::Read, os::unix::io::AsRawFd};
+
+    let mut tty = File::open("/dev/tty")?;
+    let deadline = Instant::now() + timeout;
+    let mut reply = Vec::new();
+    loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        let mut fd = libc::pollfd {
+            fd: tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let millis = libc::c_int::try_from(left.as_millis()).unwrap_or(libc::c_int::MAX);
+        // SAFETY: `fd` is a single valid pollfd that outlives the call.
+        match unsafe { libc::poll(&mut fd, 1, millis) } {
+            0 => {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "The cursor position could not be read within a normal duration",
+                ))
+            }
+            n if n < 0 => {
+                let error = io::Error::last_os_error();
+                if error.kind() != io::ErrorKind::Interrupted {
+                    return Err(error);
+                }
+                continue;
+            }
+            _ => (),
+        }
+
+        let mut byte = [0];
+        tty.read_exact(&mut byte)?;
+        reply.push(byte[0]);
+        if byte[0] == b'R' {
+            if let Some(position) = parse_position(&reply) {
+                return Ok(position);
+            }
+        }
+    }
+}
+
+/// Parses the last `ESC [ row ; column R` in a reply into a zero-based (column, row),
+/// skipping anything typed before it.
+#[cfg(unix)]
+fn parse_position(reply: &[u8]) -> Option<(u16, u16)> {
+    let reply = std::str::from_utf8(reply).ok()?;
+    let (_, report) = reply.rsplit_once("\x1b[")?;
+    let (row, column) = report.strip_suffix('R')?.split_once(';')?;
+    let row: u16 = row.parse().ok()?;
+    let column: u16 = column.parse().ok()?;
+    Some((column.saturating_sub(1), row.saturating_sub(1)))
+}
+
+/// A cell of a [`TestBackend`], holding one grapheme cluster.
+/// The cell after a double width grapheme holds an empty symbol.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub symbol: String,
+    pub style: Style,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            symbol: " ".to_owned(),
+            style: Style::Plain,
+        }
+    }
+}
+
+/// An in-memory terminal, for testing how forms are drawn.
+///
+/// ```
+/// use ibox::{Form, ScriptedEvents, TestBackend};
+///
+/// let mut backend = TestBackend::new(20, 4);
+/// Form::new("Title")
+///     .label("Hello")
+///     .length(2)
+///     .position(0, 0)
+///     .run_with(&mut backend, &mut ScriptedEvents::default())
+///     .unwrap();
+/// assert_eq!(
+///     backend.lines(),
+///     ["┌─Title──┐", "│Hello   │", "└────────┘", ""]
+/// );
+/// ```
+pub struct TestBackend {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+    cursor: (u16, u16),
+    raw_mode: bool,
+}
+
+impl TestBackend {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::default(); width as usize * height as usize],
+            cursor: (0, 0),
+            raw_mode: false,
+        }
+    }
+
+    pub fn cell(&self, x: u16, y: u16) -> &Cell {
+        &self.cells[y as usize * self.width as usize + x as usize]
+    }
+
+    pub fn cursor(&self) -> (u16, u16) {
+        self.cursor
+    }
+
+    pub fn is_raw_mode(&self) -> bool {
+        self.raw_mode
+    }
+
+    /// Returns the text on each row, without trailing spaces.
+    pub fn lines(&self) -> Vec<String> {
+        self.cells
+            .chunks(self.width as usize)
+            .map(|row| {
+                let line: String = row.iter().map(|cell| cell.symbol.as_str()).collect();
+                line.trim_end().to_owned()
+            })
+            .collect()
+    }
+
+    fn newline(&mut self) {
+        self.cursor.0 = 0;
+        if self.cursor.1 + 1 < self.height {
+            self.cursor.1 += 1;
+        } else {
+            self.cells.drain(..self.width as usize);
+            self.cells
+                .extend(std::iter::repeat_n(Cell::default(), self.width as usize));
+        }
+    }
+}
+
+impl Backend for TestBackend {
+    fn size(&mut self) -> io::Result<(u16, u16)> {
+        Ok((self.width, self.height))
+    }
+
+    fn cursor_position(&mut self) -> io::Result<(u16, u16)> {
+        Ok(self.cursor)
+    }
+
+    fn move_to(&mut self, x: u16, y: u16) -> io::Result<()> {
+        self.cursor = (
+            x.min(self.width.saturating_sub(1)),
+            y.min(self.height.saturating_sub(1)),
+        );
+        Ok(())
+    }
+
+    fn print(&mut self, text: &str, style: Style) -> io::Result<()> {
+        for symbol in text.graphemes(true) {
+            if symbol == "\n" {
+                self.newline();
+                continue;
+            }
+
+            let (x, y) = self.cursor;
+            let width = symbol.width() as u16;
+            // Zero-width graphemes still take a cell, which must be on the screen.
+            if x < self.width && x.saturating_add(width) <= self.width {
+                let i = y as usize * self.width as usize + x as usize;
+                self.cells[i] = Cell {
+                    symbol: symbol.to_owned(),
+                    style,
+                };
+                for cell in &mut self.cells[i + 1..i + (width as usize).max(1)] {
+                    *cell = Cell {
+                        symbol: String::new(),
+                        style,
+                    };
+                }
+                self.cursor.0 += width;
+            }
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn enable_raw_mode(&mut self) -> io::Result<()> {
+        self.raw_mode = true;
+        Ok(())
+    }
+
+    fn disable_raw_mode(&mut self) -> io::Result<()> {
+        self.raw_mode = false;
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+/// The characters used to draw the box, in the order
+/// top left, horizontal, top right, vertical, bottom left, bottom right.
+#[derive(Clone, Copy, Debug)]
+pub struct Border(pub [char; 6]);
+
+impl Border {
+    pub const SINGLE: Self = Self(['┌', '─', '┐', '│', '└', '┘']);
+    pub const DOUBLE: Self = Self(['╔', '═', '╗', '║', '╚', '╝']);
+    pub const THICK: Self = Self(['┏', '━', '┓', '┃', '┗', '┛']);
+    pub const CURVED: Self = Self(['╭', '─', '╮', '│', '╰', '╯']);
+
+    /// Parses a preset name or a string of exactly 6 border characters.
+    pub fn parse(border: &str) -> Result<Self, String> {
+        match border {
+            "single" => Ok(Self::SINGLE),
+            "double" => Ok(Self::DOUBLE),
+            "thick" => Ok(Self::THICK),
+            "curved" => Ok(Self::CURVED),
+            _ => {
+                let chars = border.chars().collect::<Vec<char>>();
+                chars
+                    .try_into()
+                    .map(Self)
+                    .map_err(|chars: Vec<char>| format!("Invalid border length: {}", chars.len()))
+            }
+        }
+    }
+}
+
+impl Default for Border {
+    fn default() -> Self {
+        Self::SINGLE
+    }
+}
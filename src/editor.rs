@@ -0,0 +1,345 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// A single line of text, edited a grapheme cluster at a time.
+/// `cursor` and `scroll` are byte offsets on grapheme boundaries.
+#[derive(Default)]
+pub struct LineEditor {
+    buffer: String,
+    cursor: usize,
+    scroll: usize,
+}
+
+impl LineEditor {
+    pub fn with_text(text: &str) -> Self {
+        Self {
+            buffer: text.to_owned(),
+            cursor: text.len(),
+            scroll: 0,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.buffer.clone()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Returns the display column of the cursor from the start of the buffer.
+    pub fn column(&self) -> usize {
+        self.buffer[..self.cursor].width()
+    }
+
+    /// Moves the cursor to the last grapheme boundary at or before a display column.
+    pub fn set_column(&mut self, column: usize) {
+        self.cursor = 0;
+        for (i, grapheme) in self.buffer.grapheme_indices(true) {
+            if self.buffer[..i + grapheme.len()].width() > column {
+                break;
+            }
+            self.cursor = i + grapheme.len();
+        }
+    }
+
+    /// Splits off the text after the cursor into a new editor.
+    pub fn split_off(&mut self) -> Self {
+        let rest = self.buffer.split_off(self.cursor);
+        Self {
+            buffer: rest,
+            cursor: 0,
+            scroll: 0,
+        }
+    }
+
+    /// Appends the text of another editor, leaving the cursor where the two meet.
+    pub fn join(&mut self, other: Self) {
+        self.cursor = self.buffer.len();
+        self.buffer.push_str(&other.buffer);
+    }
+
+    /// Returns the part of the buffer visible in a field of the given display width
+    /// and the column of the cursor within it, scrolling to keep the cursor visible.
+    /// If `mask` is given, every grapheme is shown as it.
+    pub fn view(&mut self, width: usize, mask: Option<char>) -> (String, usize) {
+        let width = width.max(1);
+        let measure = |text: &str| match mask {
+            Some(mask) => text.graphemes(true).count() * mask.width().unwrap_or(1),
+            None => text.width(),
+        };
+
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        }
+        while self.scroll < self.cursor && measure(&self.buffer[self.scroll..self.cursor]) >= width
+        {
+            self.scroll = self.next_boundary(self.scroll);
+        }
+
+        let mut visible = String::new();
+        let mut used = 0;
+        for grapheme in self.buffer[self.scroll..].graphemes(true) {
+            let grapheme_width = measure(grapheme);
+            if used + grapheme_width > width {
+                break;
+            }
+            used += grapheme_width;
+            match mask {
+                Some(mask) => visible.push(mask),
+                None => visible.push_str(grapheme),
+            }
+        }
+
+        (visible, measure(&self.buffer[self.scroll..self.cursor]))
+    }
+
+    /// Applies a key event to the buffer.
+    /// Returns false if the key is not an editing key.
+    pub fn handle(&mut self, event: KeyEvent) -> bool {
+        let ctrl = event.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = event.modifiers.contains(KeyModifiers::ALT);
+
+        match event.code {
+            KeyCode::Char('a') if ctrl => self.home(),
+            KeyCode::Char('e') if ctrl => self.end(),
+            KeyCode::Char('b') if ctrl => self.left(),
+            KeyCode::Char('f') if ctrl => self.right(),
+            KeyCode::Char('b') if alt => self.word_left(),
+            KeyCode::Char('f') if alt => self.word_right(),
+            KeyCode::Char('h') if ctrl => self.backspace(),
+            KeyCode::Char('w') if ctrl => self.delete_word(),
+            KeyCode::Char('u') if ctrl => self.kill_start(),
+            KeyCode::Char('k') if ctrl => self.kill_end(),
+            KeyCode::Char(_) if ctrl || alt => return false,
+            KeyCode::Char(c) => self.insert(c),
+            KeyCode::Backspace if ctrl || alt => self.delete_word(),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Left if ctrl || alt => self.word_left(),
+            KeyCode::Right if ctrl || alt => self.word_right(),
+            KeyCode::Left => self.left(),
+            KeyCode::Right => self.right(),
+            KeyCode::Home => self.home(),
+            KeyCode::End => self.end(),
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn insert(&mut self, c: char) {
+        self.buffer.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+        // Keep the cursor on a grapheme boundary if a combining character follows it.
+        if !self.is_boundary(self.cursor) {
+            self.cursor = self.next_boundary(self.cursor);
+        }
+    }
+
+    fn backspace(&mut self) {
+        let start = self.previous_boundary(self.cursor);
+        self.buffer.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    fn delete(&mut self) {
+        let end = self.next_boundary(self.cursor);
+        self.buffer.drain(self.cursor..end);
+    }
+
+    fn left(&mut self) {
+        self.cursor = self.previous_boundary(self.cursor);
+    }
+
+    fn right(&mut self) {
+        self.cursor = self.next_boundary(self.cursor);
+    }
+
+    fn home(&mut self) {
+        self.cursor = 0;
+    }
+
+    fn end(&mut self) {
+        self.cursor = self.buffer.len();
+    }
+
+    fn word_left(&mut self) {
+        self.cursor = self.previous_word();
+    }
+
+    fn word_right(&mut self) {
+        let mut graphemes = self.buffer[self.cursor..].grapheme_indices(true).peekable();
+        while graphemes.next_if(|(_, g)| !is_word(g)).is_some() {}
+        while graphemes.next_if(|(_, g)| is_word(g)).is_some() {}
+        self.cursor += graphemes
+            .peek()
+            .map_or(self.buffer.len() - self.cursor, |(i, _)| *i);
+    }
+
+    fn delete_word(&mut self) {
+        let start = self.previous_word();
+        self.buffer.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    fn kill_start(&mut self) {
+        self.buffer.drain(..self.cursor);
+        self.cursor = 0;
+    }
+
+    fn kill_end(&mut self) {
+        self.buffer.truncate(self.cursor);
+    }
+
+    fn previous_word(&self) -> usize {
+        let mut graphemes = self.buffer[..self.cursor]
+            .grapheme_indices(true)
+            .rev()
+            .peekable();
+        let mut cursor = self.cursor;
+        while let Some((i, _)) = graphemes.next_if(|(_, g)| !is_word(g)) {
+            cursor = i;
+        }
+        while let Some((i, _)) = graphemes.next_if(|(_, g)| is_word(g)) {
+            cursor = i;
+        }
+        cursor
+    }
+
+    fn previous_boundary(&self, i: usize) -> usize {
+        self.buffer[..i]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(j, _)| j)
+    }
+
+    fn next_boundary(&self, i: usize) -> usize {
+        self.buffer[i..]
+            .graphemes(true)
+            .next()
+            .map_or(i, |g| i + g.len())
+    }
+
+    fn is_boundary(&self, i: usize) -> bool {
+        self.buffer.grapheme_indices(true).any(|(j, _)| j == i) || i == self.buffer.len()
+    }
+}
+
+/// Several lines of text, edited with a [`LineEditor`] per line.
+/// `row` is the line with the cursor and `top` the first visible line.
+pub struct TextArea {
+    lines: Vec<LineEditor>,
+    row: usize,
+    top: usize,
+}
+
+impl TextArea {
+    pub fn with_text(text: &str) -> Self {
+        let lines: Vec<LineEditor> = text.split('\n').map(LineEditor::with_text).collect();
+        Self {
+            row: lines.len() - 1,
+            lines,
+            top: 0,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        let lines: Vec<String> = self.lines.iter().map(LineEditor::text).collect();
+        lines.join("\n")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.len() == 1 && self.lines[0].is_empty()
+    }
+
+    /// Returns the lines visible in an area of the given size and the column and row
+    /// of the cursor within it, scrolling to keep the cursor visible.
+    pub fn view(
+        &mut self,
+        width: usize,
+        height: usize,
+        mask: Option<char>,
+    ) -> (Vec<String>, (usize, usize)) {
+        let height = height.max(1);
+        if self.row < self.top {
+            self.top = self.row;
+        } else if self.row >= self.top + height {
+            self.top = self.row + 1 - height;
+        }
+
+        let mut column = 0;
+        let mut rows = Vec::new();
+        for (i, line) in self
+            .lines
+            .iter_mut()
+            .enumerate()
+            .skip(self.top)
+            .take(height)
+        {
+            let (text, cursor) = line.view(width, mask);
+            if i == self.row {
+                column = cursor;
+            }
+            rows.push(text);
+        }
+
+        (rows, (column, self.row - self.top))
+    }
+
+    /// Applies a key event to the text, moving between lines with Enter, the arrow keys
+    /// and deletions at either end of a line.
+    /// Returns false if the key is not an editing key or the cursor cannot move further.
+    pub fn handle(&mut self, event: KeyEvent) -> bool {
+        let ctrl = event.modifiers.contains(KeyModifiers::CONTROL);
+        let line = &self.lines[self.row];
+        let (start, end) = (line.cursor == 0, line.cursor == line.buffer.len());
+        let first = self.row == 0;
+        let last = self.row + 1 == self.lines.len();
+
+        match event.code {
+            KeyCode::Enter => {
+                let rest = self.lines[self.row].split_off();
+                self.row += 1;
+                self.lines.insert(self.row, rest);
+            }
+            KeyCode::Up if !first => self.move_to_row(self.row - 1),
+            KeyCode::Down if !last => self.move_to_row(self.row + 1),
+            KeyCode::Up | KeyCode::Down => return false,
+            KeyCode::Backspace if start && !first => self.join_previous(),
+            KeyCode::Char('h') if ctrl && start && !first => self.join_previous(),
+            KeyCode::Delete if end && !last => {
+                self.row += 1;
+                self.join_previous();
+            }
+            KeyCode::Left if start && !first => {
+                self.row -= 1;
+                self.lines[self.row].end();
+            }
+            KeyCode::Right if end && !last => {
+                self.row += 1;
+                self.lines[self.row].home();
+            }
+            _ => return self.lines[self.row].handle(event),
+        }
+
+        true
+    }
+
+    fn move_to_row(&mut self, row: usize) {
+        let column = self.lines[self.row].column();
+        self.row = row;
+        self.lines[row].set_column(column);
+    }
+
+    fn join_previous(&mut self) {
+        let line = self.lines.remove(self.row);
+        self.row -= 1;
+        self.lines[self.row].join(line);
+    }
+}
+
+fn is_word(grapheme: &str) -> bool {
+    grapheme.chars().next().is_some_and(char::is_alphanumeric)
+}
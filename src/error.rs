@@ -0,0 +1,35 @@
+use std::{fmt, io};
+
+#[derive(Debug)]
+pub enum Error {
+    /// The prompt was cancelled by the user.
+    Cancelled,
+    /// The terminal is smaller than the box needs, in columns and rows.
+    TooSmall {
+        width: u16,
+        height: u16,
+    },
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cancelled => write!(f, "Cancelled"),
+            Self::TooSmall { width, height } => write!(
+                f,
+                "Terminal is too small for the box, which needs at least {}x{}",
+                width, height
+            ),
+            Self::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
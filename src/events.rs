@@ -0,0 +1,162 @@
+use std::{
+    collections::VecDeque,
+    io::{self, ErrorKind},
+    time::Duration,
+};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+
+/// Where a form's input comes from.
+pub trait EventSource {
+    fn read(&mut self) -> io::Result<Event>;
+
+    /// Waits up to `timeout` for an event, returning whether one can be read.
+    fn poll(&mut self, _timeout: Duration) -> io::Result<bool> {
+        Ok(true)
+    }
+
+    /// Whether events are read from the terminal, which must then be in raw mode.
+    fn is_terminal(&self) -> bool {
+        false
+    }
+}
+
+/// Reads events from the terminal.
+#[derive(Default)]
+pub struct TerminalEvents;
+
+impl EventSource for TerminalEvents {
+    fn read(&mut self) -> io::Result<Event> {
+        event::read()
+    }
+
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool> {
+        event::poll(timeout)
+    }
+
+    fn is_terminal(&self) -> bool {
+        true
+    }
+}
+
+/// Replays a fixed sequence of events, failing once they run out.
+/// Polling after the last event times out right away.
+///
+/// ```
+/// use ibox::{Field, Form, ScriptedEvents, TestBackend};
+///
+/// let mut backend = TestBackend::new(20, 4);
+/// let mut events = ScriptedEvents::parse("hello\nCtrl-W\nbye\nEnter").unwrap();
+/// let answers = Form::new("Title")
+///     .field("Name: ", Field::text())
+///     .position(0, 0)
+///     .run_with(&mut backend, &mut events)
+///     .unwrap();
+/// assert_eq!(answers.get("Name"), Some("bye"));
+/// ```
+#[derive(Default)]
+pub struct ScriptedEvents {
+    events: VecDeque<Event>,
+}
+
+impl ScriptedEvents {
+    pub fn new(events: impl IntoIterator<Item = Event>) -> Self {
+        Self {
+            events: events.into_iter().collect(),
+        }
+    }
+
+    /// Parses a script with one key per line, such as `a`, `Enter` or `Ctrl-W`.
+    /// Lines that are not a single key are typed out character by character,
+    /// and empty lines or lines starting with `#` are ignored.
+    pub fn parse(script: &str) -> Result<Self, String> {
+        let mut events = VecDeque::new();
+        for line in script.lines() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match parse_key(line) {
+                Some(key) => events.push_back(Event::Key(key)),
+                None if MODIFIERS.iter().any(|m| line.starts_with(m)) => {
+                    return Err(format!("Invalid key: {}", line))
+                }
+                None => events.extend(
+                    line.chars()
+                        .map(|c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))),
+                ),
+            }
+        }
+
+        Ok(Self { events })
+    }
+}
+
+impl EventSource for ScriptedEvents {
+    fn read(&mut self) -> io::Result<Event> {
+        self.events
+            .pop_front()
+            .ok_or_else(|| io::Error::new(ErrorKind::UnexpectedEof, "Ran out of scripted events"))
+    }
+
+    fn poll(&mut self, _timeout: Duration) -> io::Result<bool> {
+        Ok(!self.events.is_empty())
+    }
+}
+
+const MODIFIERS: [&str; 3] = ["Ctrl-", "Alt-", "Shift-"];
+
+/// Parses a key named like `a`, `Enter`, `Ctrl-W` or `Alt-Enter`.
+pub fn parse_key(key: &str) -> Option<KeyEvent> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = key;
+    loop {
+        if let Some(stripped) = rest.strip_prefix("Ctrl-") {
+            modifiers |= KeyModifiers::CONTROL;
+            rest = stripped;
+        } else if let Some(stripped) = rest.strip_prefix("Alt-") {
+            modifiers |= KeyModifiers::ALT;
+            rest = stripped;
+        } else if let Some(stripped) = rest.strip_prefix("Shift-") {
+            modifiers |= KeyModifiers::SHIFT;
+            rest = stripped;
+        } else {
+            break;
+        }
+    }
+
+    let code = match rest {
+        "Enter" => KeyCode::Enter,
+        // Terminals report Shift-Tab as BackTab, keeping the shift modifier.
+        "Tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+        "Tab" => KeyCode::Tab,
+        "BackTab" => KeyCode::BackTab,
+        "Backspace" => KeyCode::Backspace,
+        "Delete" => KeyCode::Delete,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "Esc" => KeyCode::Esc,
+        "Space" => KeyCode::Char(' '),
+        _ => {
+            let mut chars = rest.chars();
+            match (chars.next(), chars.next()) {
+                // Terminals report Ctrl-W and Alt-B as a lowercase letter with the modifier.
+                (Some(c), None)
+                    if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+                {
+                    KeyCode::Char(c.to_ascii_lowercase())
+                }
+                (Some(c), None) => KeyCode::Char(c),
+                _ => return None,
+            }
+        }
+    };
+
+    Some(KeyEvent::new(code, modifiers))
+}
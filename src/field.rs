@@ -0,0 +1,275 @@
+use crate::{layout::display_width, validate::Validator};
+
+/// How typed characters are shown in a field.
+#[derive(Clone, Copy, Debug)]
+pub enum Echo {
+    Normal,
+    Mask(char),
+    Hidden,
+}
+
+/// An option of a list field, answered with its value and shown as its label.
+#[derive(Clone, Debug)]
+pub struct Choice {
+    pub value: String,
+    pub label: String,
+}
+
+impl Choice {
+    pub fn new(value: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            label: label.into(),
+        }
+    }
+
+    /// Parses an option written as `value=Label`, or just `value` to show the value itself.
+    pub fn parse(option: &str) -> Self {
+        let (value, label) = option.split_once('=').unwrap_or((option, option));
+        Self::new(value, label)
+    }
+}
+
+impl From<&str> for Choice {
+    fn from(value: &str) -> Self {
+        Self::new(value, value)
+    }
+}
+
+impl From<(&str, &str)> for Choice {
+    fn from((value, label): (&str, &str)) -> Self {
+        Self::new(value, label)
+    }
+}
+
+/// What kind of input a field accepts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Text,
+    Select,
+    Checklist,
+    Pick,
+    Buttons,
+}
+
+/// An input field, added to a [`Form`](crate::Form) after a label.
+#[derive(Clone, Debug)]
+pub struct Field {
+    pub(crate) name: String,
+    pub(crate) echo: Echo,
+    pub(crate) default: String,
+    pub(crate) placeholder: String,
+    pub(crate) validators: Vec<Validator>,
+    pub(crate) rows: u16,
+    pub(crate) options: Vec<Choice>,
+    pub(crate) kind: Kind,
+}
+
+impl Field {
+    /// A plain text field.
+    pub fn text() -> Self {
+        Self {
+            name: String::new(),
+            echo: Echo::Normal,
+            default: String::new(),
+            placeholder: String::new(),
+            validators: Vec::new(),
+            rows: 1,
+            options: Vec::new(),
+            kind: Kind::Text,
+        }
+    }
+
+    /// A text field whose input is masked with `*`.
+    pub fn password() -> Self {
+        Self::text().echo(Echo::Mask('*'))
+    }
+
+    /// A text field spanning four rows that accepts multiple lines of input.
+    /// Enter starts a new line and the form's submit key moves on.
+    ///
+    /// ```
+    /// use ibox::{Field, Form, ScriptedEvents, TestBackend};
+    ///
+    /// let mut backend = TestBackend::new(20, 8);
+    /// let mut events = ScriptedEvents::parse("one\nEnter\ntwo\nAlt-Enter").unwrap();
+    /// let answers = Form::new("Title")
+    ///     .field("Notes: ", Field::textarea())
+    ///     .position(0, 0)
+    ///     .run_with(&mut backend, &mut events)
+    ///     .unwrap();
+    /// assert_eq!(answers.get("Notes"), Some("one\ntwo"));
+    /// ```
+    pub fn textarea() -> Self {
+        Self::text().rows(4)
+    }
+
+    /// A list of options, of which the one chosen with Enter is the answer.
+    /// All options are shown unless the number of rows is limited.
+    ///
+    /// ```
+    /// use ibox::{Field, Form, ScriptedEvents, TestBackend};
+    ///
+    /// let mut backend = TestBackend::new(24, 6);
+    /// let mut events = ScriptedEvents::parse("Down\nk\nj\nEnter").unwrap();
+    /// let answers = Form::new("Deploy")
+    ///     .field("Env: ", Field::select([("dev", "Development"), ("prod", "Production")]))
+    ///     .position(0, 0)
+    ///     .run_with(&mut backend, &mut events)
+    ///     .unwrap();
+    /// assert_eq!(answers.get("Env"), Some("prod"));
+    /// ```
+    pub fn select<C: Into<Choice>>(options: impl IntoIterator<Item = C>) -> Self {
+        let options: Vec<Choice> = options.into_iter().map(Into::into).collect();
+        Self {
+            rows: u16::try_from(options.len()).unwrap_or(u16::MAX),
+            options,
+            kind: Kind::Select,
+            ..Self::text()
+        }
+    }
+
+    /// A list of options that are checked with Space, of which every checked
+    /// option is the answer.
+    ///
+    /// ```
+    /// use ibox::{Field, Form, ScriptedEvents, TestBackend, Value};
+    ///
+    /// let mut backend = TestBackend::new(24, 7);
+    /// let mut events = ScriptedEvents::parse("Space\nDown\nDown\nSpace\nEnter").unwrap();
+    /// let answers = Form::new("Install")
+    ///     .field("Extras: ", Field::checklist(["docs", "tests", "examples"]))
+    ///     .position(0, 0)
+    ///     .run_with(&mut backend, &mut events)
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     answers.value("Extras"),
+    ///     Some(&Value::List(vec!["docs".to_owned(), "examples".to_owned()]))
+    /// );
+    /// ```
+    pub fn checklist<C: Into<Choice>>(options: impl IntoIterator<Item = C>) -> Self {
+        Self {
+            kind: Kind::Checklist,
+            ..Self::select(options)
+        }
+    }
+
+    /// A filter above a list of options, ranked by how well they fuzzily match the
+    /// filter, of which the one chosen with Enter is the answer.
+    /// Ten options are shown unless the number of rows is set.
+    ///
+    /// ```
+    /// use ibox::{Field, Form, ScriptedEvents, TestBackend};
+    ///
+    /// let mut backend = TestBackend::new(30, 16);
+    /// let mut events = ScriptedEvents::parse("rdme\nEnter").unwrap();
+    /// let answers = Form::new("Open")
+    ///     .field("> ", Field::pick(["Cargo.toml", "README.md", "src/main.rs"]).name("file"))
+    ///     .position(0, 0)
+    ///     .run_with(&mut backend, &mut events)
+    ///     .unwrap();
+    /// assert_eq!(answers.get("file"), Some("README.md"));
+    /// ```
+    pub fn pick<C: Into<Choice>>(options: impl IntoIterator<Item = C>) -> Self {
+        Self {
+            rows: 10,
+            kind: Kind::Pick,
+            ..Self::select(options)
+        }
+    }
+
+    /// A row of buttons, of which the one chosen with Enter is the answer.
+    /// Pressing the first letter of a button chooses it and moves on.
+    ///
+    /// ```
+    /// use ibox::{Field, Form, ScriptedEvents, TestBackend};
+    ///
+    /// let mut backend = TestBackend::new(24, 4);
+    /// let mut events = ScriptedEvents::parse("n").unwrap();
+    /// let answers = Form::new("Deploy?")
+    ///     .field("", Field::buttons([("yes", "Yes"), ("no", "No")]).name("deploy"))
+    ///     .position(0, 0)
+    ///     .run_with(&mut backend, &mut events)
+    ///     .unwrap();
+    /// assert_eq!(answers.get("deploy"), Some("no"));
+    /// ```
+    pub fn buttons<C: Into<Choice>>(options: impl IntoIterator<Item = C>) -> Self {
+        Self {
+            kind: Kind::Buttons,
+            rows: 1,
+            ..Self::select(options)
+        }
+    }
+
+    /// Sets the identifier of the field in the answers.
+    /// Defaults to the field's label without any trailing colon.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn echo(mut self, echo: Echo) -> Self {
+        self.echo = echo;
+        self
+    }
+
+    /// Sets the initial, editable contents of the field.
+    pub fn default(mut self, default: impl Into<String>) -> Self {
+        self.default = default.into();
+        self
+    }
+
+    /// Sets the dimmed text shown while the field is empty.
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Sets the number of rows the field takes up in the box. Text fields with more than
+    /// one row accept multiple lines, scrolling once there are more lines than rows,
+    /// and lists scroll once there are more options than rows.
+    pub fn rows(mut self, rows: u16) -> Self {
+        self.rows = rows.max(1);
+        self
+    }
+
+    /// Adds a validator that must pass before the field can be left.
+    pub fn validator(mut self, validator: Validator) -> Self {
+        self.validators.push(validator);
+        self
+    }
+
+    /// Returns the number of columns taken up by the widest option of a list
+    /// and the marker before it, leaving out the column after the label.
+    pub(crate) fn options_width(&self) -> Option<u16> {
+        let marker = match self.kind {
+            Kind::Text => return None,
+            Kind::Select | Kind::Pick => 1,
+            Kind::Checklist => 3,
+            Kind::Buttons => {
+                // Each button is drawn as `[ label ]`, with a space between buttons.
+                let width = self
+                    .options
+                    .iter()
+                    .map(|option| display_width(&option.label).saturating_add(5))
+                    .fold(0, u16::saturating_add);
+                return Some(width.saturating_sub(2));
+            }
+        };
+        self.options
+            .iter()
+            .map(|option| display_width(&option.label).saturating_add(marker))
+            .max()
+    }
+
+    /// Returns the number of rows the field takes up, which for lists is at most
+    /// the number of options, plus the filter of a picker.
+    pub(crate) fn height(&self) -> u16 {
+        let options = u16::try_from(self.options.len()).unwrap_or(u16::MAX);
+        match self.kind {
+            Kind::Text | Kind::Buttons => self.rows,
+            Kind::Select | Kind::Checklist => self.rows.min(options).max(1),
+            Kind::Pick => self.rows.min(options).max(1).saturating_add(1),
+        }
+    }
+}
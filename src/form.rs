@@ -0,0 +1,573 @@
+use std::{
+    io::{self, stderr},
+    ops::{Deref, DerefMut},
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    backend::{Backend, CrosstermBackend, Style},
+    border::Border,
+    error::Error,
+    events::{EventSource, TerminalEvents},
+    field::{Echo, Field},
+    layout::{bot, display_width, mid, top, truncate, Layout},
+    menu::Menu,
+    output::Answers,
+    prompt::{Action, Input, Prompt, Widget},
+    query,
+};
+
+/// A line of the box, which is a field if it has one.
+#[derive(Clone, Debug)]
+pub struct Line {
+    pub(crate) label: String,
+    pub(crate) field: Option<Field>,
+}
+
+/// An input box, drawn and filled in with [`Form::run`].
+#[derive(Clone, Debug)]
+pub struct Form {
+    title: String,
+    border: Border,
+    length: u16,
+    center: bool,
+    stretch: bool,
+    max_width: Option<u16>,
+    position: Option<(u16, u16)>,
+    submit_key: KeyEvent,
+    lines: Vec<Line>,
+}
+
+impl Form {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            border: Border::default(),
+            length: 8,
+            center: false,
+            stretch: false,
+            max_width: None,
+            position: None,
+            submit_key: KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT),
+            lines: Vec::new(),
+        }
+    }
+
+    pub fn border(mut self, border: Border) -> Self {
+        self.border = border;
+        self
+    }
+
+    /// Sets the added length of the input space after the longest line.
+    pub fn length(mut self, length: u16) -> Self {
+        self.length = length;
+        self
+    }
+
+    /// Centers the box on the screen.
+    pub fn center(mut self, center: bool) -> Self {
+        self.center = center;
+        self
+    }
+
+    /// Makes the box stretch to the terminal's sides.
+    pub fn stretch(mut self, stretch: bool) -> Self {
+        self.stretch = stretch;
+        self
+    }
+
+    /// Sets the maximum width of the box, wrapping labels that do not fit.
+    /// Defaults to the width of the terminal.
+    ///
+    /// ```
+    /// use ibox::{Form, ScriptedEvents, TestBackend};
+    ///
+    /// let mut backend = TestBackend::new(20, 5);
+    /// Form::new("Title")
+    ///     .label("Some long context")
+    ///     .max_width(12)
+    ///     .position(0, 0)
+    ///     .run_with(&mut backend, &mut ScriptedEvents::default())
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     backend.lines(),
+    ///     ["┌─Title────┐", "│Some long │", "│context   │", "└──────────┘", ""]
+    /// );
+    /// ```
+    pub fn max_width(mut self, max_width: u16) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Sets the position of the top left corner of the box.
+    /// Defaults to the current cursor position.
+    pub fn position(mut self, x: u16, y: u16) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    /// Sets the key that leaves a multi-line text field, like Enter does other fields.
+    /// Defaults to Alt-Enter.
+    pub fn submit_key(mut self, key: KeyEvent) -> Self {
+        self.submit_key = key;
+        self
+    }
+
+    /// Adds a line of text.
+    pub fn label(self, label: impl Into<String>) -> Self {
+        self.line(Line {
+            label: label.into(),
+            field: None,
+        })
+    }
+
+    /// Adds a field after a label.
+    pub fn field(self, label: impl Into<String>, field: Field) -> Self {
+        self.line(Line {
+            label: label.into(),
+            field: Some(field),
+        })
+    }
+
+    /// Adds a line, which is a field if it has one. Fields are named after their label
+    /// unless named otherwise, and a name that is already taken gets a `_2`, `_3`, ...
+    /// suffix so that every answer can be told apart.
+    ///
+    /// ```
+    /// use ibox::{Field, Form, Format, ScriptedEvents, TestBackend};
+    ///
+    /// let mut backend = TestBackend::new(20, 5);
+    /// let mut events = ScriptedEvents::parse("a\nEnter\nb\nEnter").unwrap();
+    /// let answers = Form::new("Title")
+    ///     .field("Q: ", Field::text())
+    ///     .field("Q: ", Field::text())
+    ///     .position(0, 0)
+    ///     .run_with(&mut backend, &mut events)
+    ///     .unwrap();
+    /// assert_eq!(answers.format(Format::Json, '\n'), "{\"Q\":\"a\",\"Q_2\":\"b\"}\n");
+    /// ```
+    pub fn line(mut self, mut line: Line) -> Self {
+        if let Some(field) = &mut line.field {
+            if field.name.is_empty() {
+                field.name = query::label_name(&line.label);
+            }
+            let name = field.name.clone();
+            let mut suffix = 1;
+            while self.field_names().any(|taken| taken == field.name) {
+                suffix += 1;
+                field.name = format!("{}_{}", name, suffix);
+            }
+        }
+        self.lines.push(line);
+        self
+    }
+
+    fn field_names(&self) -> impl Iterator<Item = &str> {
+        self.lines
+            .iter()
+            .filter_map(|line| line.field.as_ref())
+            .map(|field| field.name.as_str())
+    }
+
+    /// Computes where the box goes, querying the cursor position only if no other
+    /// position was given. The box is narrowed to fit its maximum width and the
+    /// terminal when its size is known, wrapping the labels that no longer fit.
+    fn layout<B: Backend>(&self, backend: &mut B) -> Result<Layout, Error> {
+        let size = match backend.size() {
+            Ok((width, height)) if width > 0 && height > 0 => Some((width, height)),
+            _ if self.stretch || self.center => Some(backend.size()?),
+            _ => None,
+        };
+
+        let mut length = if self.stretch {
+            u16::MAX
+        } else {
+            let longest = self
+                .lines
+                .iter()
+                .map(|line| display_width(&line.label))
+                .chain([display_width(&self.title)])
+                .max()
+                .unwrap_or_default()
+                .saturating_add(self.length);
+            // Lists are widened to fit their options.
+            self.lines
+                .iter()
+                .filter_map(|line| {
+                    let field = line.field.as_ref()?;
+                    Some(display_width(&line.label).saturating_add(field.options_width()?))
+                })
+                .fold(longest, u16::max)
+        };
+        if let Some(max_width) = self.max_width {
+            length = length.min(max_width.saturating_sub(3).max(1));
+        }
+
+        let (mut x, y) = match (self.center, size) {
+            (true, Some((width, _))) => {
+                length = length.min(width.saturating_sub(3));
+                ((width / 2).saturating_sub(length / 2 + 2), 0)
+            }
+            _ => match self.position {
+                Some(position) => position,
+                None => backend.cursor_position()?,
+            },
+        };
+
+        if self.stretch {
+            x = 0;
+        }
+
+        // Without a known size, the box must still fit in the coordinates of a terminal.
+        let (width, height) = size.unwrap_or((u16::MAX, u16::MAX));
+        length = length.min(width.saturating_sub(x).saturating_sub(3));
+
+        let mut layout = Layout {
+            x,
+            y,
+            length,
+            rows: 0,
+            input: self.length.min(length / 2).max(1),
+        };
+        if length > 0 {
+            layout.rows = self
+                .lines
+                .iter()
+                .map(|line| match &line.field {
+                    Some(field) => {
+                        layout.wrap(&line.label, true).len() + field.height() as usize - 1
+                    }
+                    None => layout.wrap(&line.label, false).len(),
+                })
+                .sum::<usize>()
+                .try_into()
+                .unwrap_or(u16::MAX);
+        }
+
+        // The box needs a column for input and a line under it for errors. The terminal
+        // scrolls to make room below the cursor, which coordinates cannot do.
+        let fits = match size {
+            Some(_) => length > 0 && layout.height() < height,
+            None => x <= width - 3 && y.saturating_add(layout.height()) < height,
+        };
+        if !fits {
+            return Err(Error::TooSmall {
+                width: x.saturating_add(4),
+                height: layout.height().saturating_add(1),
+            });
+        }
+        if self.center && size.is_some() {
+            layout.y = (height / 2).saturating_sub(layout.rows.div_ceil(2));
+        }
+
+        Ok(layout)
+    }
+
+    /// Draws the box on stderr and lets the user fill in its fields.
+    pub fn run(self) -> Result<Answers, Error> {
+        self.run_with(&mut CrosstermBackend::new(stderr()), &mut TerminalEvents)
+    }
+
+    /// Draws the box on the given backend and fills in its fields from the given events.
+    pub fn run_with<B: Backend, E: EventSource>(
+        self,
+        backend: &mut B,
+        events: &mut E,
+    ) -> Result<Answers, Error> {
+        let submit_key = self.submit_key;
+        let (layout, inputs) = self.draw(backend)?;
+        let origin = layout.origin();
+        let size = layout.size();
+        let (ex, ey) = (0, layout.y + layout.height());
+
+        let mut answers = Answers::default();
+        if inputs.is_empty() {
+            return Ok(answers);
+        }
+
+        let mut backend = RawMode::enable(backend, events.is_terminal())?;
+        let mut prompt = Prompt::new(inputs, submit_key);
+        for input in prompt.inputs.iter_mut() {
+            draw_input(&mut *backend, input)?;
+        }
+
+        loop {
+            draw_error(&mut *backend, origin, size, prompt.error.as_deref())?;
+            draw_input(&mut *backend, prompt.focused())?;
+            if let Event::Key(event) = events.read()? {
+                match prompt.handle(event) {
+                    Action::Continue => (),
+                    Action::Submit => break,
+                    Action::Cancel => {
+                        clear(&mut *backend, origin, size)?;
+                        return Err(Error::Cancelled);
+                    }
+                }
+            }
+        }
+
+        // Show the final state of the field that submitted, such as a pressed button.
+        draw_input(&mut *backend, prompt.focused())?;
+        draw_error(&mut *backend, origin, size, None)?;
+        backend.move_to(ex, ey)?;
+        backend.flush()?;
+        drop(backend);
+
+        for input in prompt.inputs {
+            let value = input.widget.value();
+            answers.push(input.name, value);
+        }
+
+        Ok(answers)
+    }
+
+    /// Draws the box on stderr as a message with an OK button, waiting for a key
+    /// or until `timeout` has passed before clearing it.
+    pub fn show(self, timeout: Option<Duration>) -> Result<(), Error> {
+        self.show_with(
+            &mut CrosstermBackend::new(stderr()),
+            &mut TerminalEvents,
+            timeout,
+        )
+    }
+
+    /// Draws the box on the given backend as a message with an OK button, waiting for
+    /// a key from the given events or until `timeout` has passed before clearing it.
+    /// The box is meant to hold only labels, as fields are not filled in.
+    ///
+    /// ```
+    /// use ibox::{Form, ScriptedEvents, TestBackend};
+    ///
+    /// let mut backend = TestBackend::new(24, 5);
+    /// let mut events = ScriptedEvents::parse("Enter").unwrap();
+    /// Form::new("Done")
+    ///     .label("Backup finished.")
+    ///     .position(0, 0)
+    ///     .show_with(&mut backend, &mut events, None)
+    ///     .unwrap();
+    /// assert!(backend.lines().iter().all(|line| line.trim().is_empty()));
+    /// ```
+    pub fn show_with<B: Backend, E: EventSource>(
+        self,
+        backend: &mut B,
+        events: &mut E,
+        timeout: Option<Duration>,
+    ) -> Result<(), Error> {
+        let form = self.field("", Field::buttons(["OK"]).name("ok"));
+        let (layout, mut inputs) = form.draw(backend)?;
+        let mut backend = RawMode::enable(backend, events.is_terminal())?;
+        if let Some(button) = inputs.last_mut() {
+            draw_input(&mut *backend, button)?;
+        }
+
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            if let Some(deadline) = deadline {
+                let left = deadline.saturating_duration_since(Instant::now());
+                if left.is_zero() || !events.poll(left)? {
+                    break;
+                }
+            }
+            if let Event::Key(_) = events.read()? {
+                break;
+            }
+        }
+
+        clear(&mut *backend, layout.origin(), layout.size())?;
+        Ok(())
+    }
+
+    /// Draws the border, labels and blank fields of the box, returning its layout
+    /// and the inputs to fill the fields in with.
+    fn draw<B: Backend>(self, backend: &mut B) -> Result<(Layout, Vec<Input>), Error> {
+        let mut layout = self.layout(backend)?;
+        layout.make_room(backend)?;
+        let border = self.border.0;
+        let length = layout.length;
+        let mut inputs: Vec<Input> = Vec::new();
+
+        backend.move_to(layout.x, layout.y)?;
+        let title = truncate(&self.title, length as usize);
+        backend.print(&top(title, &border, length), Style::Plain)?;
+        let mut row = 0;
+        for line in self.lines {
+            let rows = layout.wrap(&line.label, line.field.is_some());
+            for label in &rows {
+                backend.move_to(layout.x, layout.y.saturating_add(1 + row))?;
+                backend.print(&mid(label, &border, length), Style::Plain)?;
+                row += 1;
+            }
+            if let Some(field) = line.field {
+                let (x, y, width) = layout.field(row - 1, rows[rows.len() - 1]);
+                for _ in 1..field.height() {
+                    backend.move_to(layout.x, layout.y.saturating_add(1 + row))?;
+                    backend.print(&mid("", &border, length), Style::Plain)?;
+                    row += 1;
+                }
+                inputs.push(Input::new(x, y, width, field));
+            }
+        }
+        backend.move_to(layout.x, layout.y + layout.height() - 1)?;
+        backend.print(&bot(&border, length), Style::Plain)?;
+
+        // Leave the cursor on the line under the box, like printing the box would.
+        backend.move_to(0, layout.y + layout.height())?;
+        backend.flush()?;
+
+        Ok((layout, inputs))
+    }
+}
+
+fn draw_input<B: Backend>(backend: &mut B, input: &mut Input) -> io::Result<()> {
+    let (x, y, width, height) = (input.x, input.y, input.width, input.height);
+    let editor = match &mut input.widget {
+        Widget::Text(editor) => editor,
+        Widget::Menu(menu) => {
+            let cursor = draw_menu(backend, (x, y, width, height), menu)?;
+            backend.move_to(x, y + cursor)?;
+            return backend.flush();
+        }
+        Widget::Buttons(buttons) => {
+            let mut used = 0;
+            let mut cursor = 0;
+            backend.move_to(x, y)?;
+            for (i, (label, chosen)) in buttons.view().enumerate() {
+                let label = truncate(&label, (width as usize).saturating_sub(used + i.min(1)));
+                if i > 0 && !label.is_empty() {
+                    backend.print(" ", Style::Plain)?;
+                    used += 1;
+                }
+                if chosen {
+                    cursor = used;
+                    backend.print(label, Style::Selected)?;
+                } else {
+                    backend.print(label, Style::Plain)?;
+                }
+                used += label.width();
+            }
+            let padding = (width as usize).saturating_sub(used);
+            backend.print(&" ".repeat(padding), Style::Plain)?;
+            backend.move_to(x + cursor as u16, y)?;
+            return backend.flush();
+        }
+        Widget::Pick(picker) => {
+            let (text, column) = picker.filter.view(width as usize, None);
+            let padding = (width as usize).saturating_sub(text.width());
+            backend.move_to(x, y)?;
+            backend.print(&format!("{}{}", text, " ".repeat(padding)), Style::Plain)?;
+            draw_menu(backend, (x, y + 1, width, height - 1), &mut picker.menu)?;
+            backend.move_to(x + column as u16, y)?;
+            return backend.flush();
+        }
+    };
+    let (mut rows, (column, row)) = match input.echo {
+        Echo::Normal => editor.view(width as usize, height as usize, None),
+        Echo::Mask(c) => editor.view(width as usize, height as usize, Some(c)),
+        Echo::Hidden => (Vec::new(), (0, 0)),
+    };
+    rows.resize(height as usize, String::new());
+    let placeholder = editor.is_empty() && !input.placeholder.is_empty();
+    for (i, text) in rows.iter().enumerate() {
+        backend.move_to(x, y + i as u16)?;
+        if placeholder && i == 0 {
+            let placeholder = truncate(&input.placeholder, width as usize);
+            let padding = (width as usize).saturating_sub(placeholder.width());
+            backend.print(placeholder, Style::Placeholder)?;
+            backend.print(&" ".repeat(padding), Style::Plain)?;
+        } else {
+            let padding = (width as usize).saturating_sub(text.width());
+            backend.print(&format!("{}{}", text, " ".repeat(padding)), Style::Plain)?;
+        }
+    }
+    backend.move_to(x + column as u16, y + row as u16)?;
+    backend.flush()
+}
+
+/// Draws the visible options of a list, highlighting the one under the cursor,
+/// and returns the row of the cursor.
+fn draw_menu<B: Backend>(
+    backend: &mut B,
+    (x, y, width, height): (u16, u16, u16, u16),
+    menu: &mut Menu,
+) -> io::Result<u16> {
+    let (mut rows, cursor) = menu.view(height as usize);
+    let highlighted = cursor < rows.len();
+    rows.resize(height as usize, String::new());
+    for (i, text) in rows.iter().enumerate() {
+        let text = truncate(text, width as usize);
+        let padding = (width as usize).saturating_sub(text.width());
+        let style = if i == cursor && highlighted {
+            Style::Selected
+        } else {
+            Style::Plain
+        };
+        backend.move_to(x, y + i as u16)?;
+        backend.print(&format!("{}{}", text, " ".repeat(padding)), style)?;
+    }
+    Ok(cursor as u16)
+}
+
+/// Draws a validation error on the line under the box, or clears it.
+fn draw_error<B: Backend>(
+    backend: &mut B,
+    origin: (u16, u16),
+    size: (u16, u16),
+    error: Option<&str>,
+) -> io::Result<()> {
+    let (x, y) = origin;
+    let (width, height) = size;
+    let message = truncate(error.unwrap_or_default(), width as usize);
+    let padding = (width as usize).saturating_sub(message.width());
+    backend.move_to(x, y + height)?;
+    backend.print(message, Style::Error)?;
+    backend.print(&" ".repeat(padding), Style::Plain)
+}
+
+/// Erases the box and the line under it.
+fn clear<B: Backend>(backend: &mut B, origin: (u16, u16), size: (u16, u16)) -> io::Result<()> {
+    let (x, y) = origin;
+    let (width, height) = size;
+    for row in 0..=height {
+        backend.move_to(x, y + row)?;
+        backend.print(&" ".repeat(width as usize), Style::Plain)?;
+    }
+    backend.move_to(x, y)?;
+    backend.flush()
+}
+
+/// Keeps the backend in raw mode until dropped, if enabled.
+struct RawMode<'a, B: Backend>(&'a mut B, bool);
+
+impl<'a, B: Backend> RawMode<'a, B> {
+    fn enable(backend: &'a mut B, enabled: bool) -> io::Result<Self> {
+        if enabled {
+            backend.enable_raw_mode()?;
+        }
+        Ok(Self(backend, enabled))
+    }
+}
+
+impl<B: Backend> Deref for RawMode<'_, B> {
+    type Target = B;
+
+    fn deref(&self) -> &B {
+        self.0
+    }
+}
+
+impl<B: Backend> DerefMut for RawMode<'_, B> {
+    fn deref_mut(&mut self) -> &mut B {
+        self.0
+    }
+}
+
+impl<B: Backend> Drop for RawMode<'_, B> {
+    fn drop(&mut self) {
+        if self.1 {
+            let _ = self.0.disable_raw_mode();
+        }
+    }
+}
@@ -0,0 +1,52 @@
+/// Scores how well `pattern` matches `text` as a case-insensitive subsequence,
+/// or returns `None` if it does not match at all. Consecutive characters and
+/// characters at the start of words score higher, while gaps score lower.
+pub fn score(pattern: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut last: Option<usize> = None;
+
+    for p in pattern.chars().filter(|c| !c.is_whitespace()) {
+        let found = (next..text.len()).find(|&i| same(text[i], p))?;
+        score += 1;
+
+        let gap = found - last.map_or(0, |last| last + 1);
+        if gap == 0 && last.is_some() {
+            score += 8;
+        } else {
+            score -= gap.min(8) as i64;
+        }
+
+        let previous = found.checked_sub(1).map(|i| text[i]);
+        match previous {
+            None => score += 8,
+            Some(c) if !c.is_alphanumeric() => score += 6,
+            Some(c) if c.is_lowercase() && text[found].is_uppercase() => score += 4,
+            _ => (),
+        }
+
+        last = Some(found);
+        next = found + 1;
+    }
+
+    Some(score)
+}
+
+/// Returns the indices of the texts that match `pattern`, best matches first.
+/// Ties keep shorter texts first, and then their original order.
+pub fn rank<'a>(pattern: &str, texts: impl IntoIterator<Item = &'a str>) -> Vec<usize> {
+    let mut matches: Vec<(usize, i64, usize)> = texts
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, text)| Some((i, score(pattern, text)?, text.len())))
+        .collect();
+    if !pattern.trim().is_empty() {
+        matches.sort_by_key(|&(i, score, len)| (-score, len, i));
+    }
+    matches.into_iter().map(|(i, _, _)| i).collect()
+}
+
+fn same(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
@@ -0,0 +1,165 @@
+use std::io;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::backend::{Backend, Style};
+
+/// Where the box is drawn, computed up front so that fields can be placed
+/// without asking the terminal where the cursor is.
+pub struct Layout {
+    pub x: u16,
+    pub y: u16,
+    /// The added length of the box after its left border and first column.
+    pub length: u16,
+    /// The number of rows between the top and bottom borders.
+    pub rows: u16,
+    /// The number of columns kept for the input area when a field's label wraps.
+    pub input: u16,
+}
+
+impl Layout {
+    pub fn width(&self) -> u16 {
+        self.length.saturating_add(3)
+    }
+
+    pub fn height(&self) -> u16 {
+        self.rows.saturating_add(2)
+    }
+
+    pub fn origin(&self) -> (u16, u16) {
+        (self.x, self.y)
+    }
+
+    pub fn size(&self) -> (u16, u16) {
+        (self.width(), self.height())
+    }
+
+    /// Splits a line's label into the rows it takes up in the box. A field's
+    /// label wraps early enough to leave room for its input area on the last row.
+    pub fn wrap<'a>(&self, label: &'a str, field: bool) -> Vec<&'a str> {
+        let width = if field {
+            self.length
+                .saturating_add(1)
+                .saturating_sub(self.input)
+                .max(1)
+        } else {
+            self.length.saturating_add(1)
+        };
+        wrap(label, width as usize)
+    }
+
+    /// Returns the position and width of the input area after a label on the given row.
+    /// The label must be the last row returned by [`Layout::wrap`].
+    pub fn field(&self, row: u16, label: &str) -> (u16, u16, u16) {
+        let label_len = display_width(label);
+        (
+            self.x.saturating_add(1).saturating_add(label_len),
+            self.y.saturating_add(1).saturating_add(row),
+            self.length.saturating_add(1).saturating_sub(label_len),
+        )
+    }
+
+    /// Scrolls the terminal up if the box and the line under it would not fit below the origin.
+    pub fn make_room<B: Backend>(&mut self, backend: &mut B) -> io::Result<()> {
+        let rows = match backend.size() {
+            Ok((_, rows)) if rows > 0 => rows,
+            _ => return Ok(()),
+        };
+
+        let overflow = self
+            .y
+            .saturating_add(self.height())
+            .saturating_add(1)
+            .saturating_sub(rows);
+        if overflow > 0 {
+            backend.move_to(0, rows - 1)?;
+            backend.print(&"\n".repeat(overflow as usize), Style::Plain)?;
+            self.y = self.y.saturating_sub(overflow);
+        }
+        Ok(())
+    }
+}
+
+pub fn top(title: &str, border: &[char], length: u16) -> String {
+    let mut top = String::new();
+    top.push(border[0]);
+    top.push(border[1]);
+    top.push_str(title);
+    for _ in 0..length.saturating_sub(display_width(title)) {
+        top.push(border[1]);
+    }
+
+    top.push(border[2]);
+    top
+}
+
+pub fn mid(text: &str, border: &[char], length: u16) -> String {
+    let mut mid = String::new();
+    mid.push(border[3]);
+    mid.push_str(text);
+    for _ in 0..length.saturating_add(1).saturating_sub(display_width(text)) {
+        mid.push(' ');
+    }
+    mid.push(border[3]);
+    mid
+}
+
+pub fn bot(border: &[char], length: u16) -> String {
+    let mut bot = String::new();
+    bot.push(border[4]);
+    bot.push(border[1]);
+    for _ in 0..length {
+        bot.push(border[1]);
+    }
+    bot.push(border[5]);
+    bot
+}
+
+/// Returns the number of columns the text takes up on the terminal, capped at `u16::MAX`.
+pub fn display_width(text: &str) -> u16 {
+    u16::try_from(text.width()).unwrap_or(u16::MAX)
+}
+
+/// Returns the longest prefix of the text that fits in the given number of columns.
+pub fn truncate(text: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (i, grapheme) in text.grapheme_indices(true) {
+        used += grapheme.width();
+        if used > width {
+            return &text[..i];
+        }
+    }
+    text
+}
+
+/// Splits the text into rows that fit in the given number of columns, breaking
+/// at whitespace where possible. Whitespace at the end of the text is kept if it fits.
+pub fn wrap(text: &str, width: usize) -> Vec<&str> {
+    let mut rows = Vec::new();
+    let mut rest = text;
+    while rest.width() > width {
+        let fit = truncate(rest, width);
+        let space = rest
+            .char_indices()
+            .take_while(|&(i, _)| i <= fit.len())
+            .filter(|&(_, c)| c.is_whitespace())
+            .map(|(i, _)| i)
+            .last();
+        let (row, next) = match space.map(|i| rest.split_at(i)) {
+            Some((row, next)) if !row.trim_end().is_empty() => (row.trim_end(), next.trim_start()),
+            // Words longer than a row are broken, making progress even if not a single
+            // grapheme fits.
+            _ => match fit.len() {
+                0 => rest.split_at(rest.graphemes(true).next().map_or(0, str::len)),
+                len => rest.split_at(len),
+            },
+        };
+        rows.push(row);
+        rest = next;
+    }
+    if rows.is_empty() || !rest.is_empty() {
+        rows.push(rest);
+    }
+    rows
+}
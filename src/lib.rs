@@ -0,0 +1,42 @@
+//! Simple input box drawing library.
+//!
+//! ```no_run
+//! use ibox::{Field, Form, Validator};
+//!
+//! let answers = Form::new("Sign up")
+//!     .label("Please fill in your details.")
+//!     .field("Username: ", Field::text().name("user").validator(Validator::NonEmpty))
+//!     .field("Password: ", Field::password())
+//!     .run()?;
+//! println!("Hello, {}!", answers.get("user").unwrap());
+//! # Ok::<(), ibox::Error>(())
+//! ```
+
+mod backend;
+mod border;
+mod editor;
+mod error;
+mod events;
+mod field;
+mod form;
+mod fuzzy;
+mod layout;
+mod menu;
+mod output;
+mod picker;
+mod prompt;
+mod query;
+mod spec;
+mod validate;
+
+pub use crossterm;
+
+pub use backend::{Backend, Cell, CrosstermBackend, Style, TestBackend};
+pub use border::Border;
+pub use error::Error;
+pub use events::{parse_key, EventSource, ScriptedEvents, TerminalEvents};
+pub use field::{Choice, Echo, Field};
+pub use form::{Form, Line};
+pub use output::{Answers, Format, Value};
+pub use spec::FormSpec;
+pub use validate::Validator;
@@ -1,34 +1,68 @@
 use std::{
     env::{self, Args},
-    io::{stderr, Stderr},
+    fs::{self, OpenOptions},
+    io::{stderr, stdin, IsTerminal, Read, Write},
     process::exit,
+    time::Duration,
 };
 
-use crossterm::{
-    event::{read, Event, KeyCode},
-    ExecutableCommand, QueueableCommand,
+use ibox::{
+    crossterm::event::KeyEvent, parse_key, Answers, Border, Choice, CrosstermBackend, Echo, Error,
+    Field, Form, FormSpec, Format, Line, ScriptedEvents, TerminalEvents, Value,
 };
 
+const EXIT_ERROR: i32 = 1;
+const EXIT_NO: i32 = 1;
+/// Used instead of [`EXIT_ERROR`] with `--confirm`, where 1 means No.
+const EXIT_CONFIRM_ERROR: i32 = 2;
+const EXIT_CANCELLED: i32 = 130;
+
 struct Config {
-    pub border: Vec<char>,
-    pub center: bool,
-    pub stretch: bool,
-    pub position: (u16, u16),
-    pub query: Vec<String>,
-    pub length: u16,
+    pub form: Form,
+    pub output: Format,
+    pub null: bool,
+    pub pick: bool,
+    pub confirm: bool,
+    pub msgbox: bool,
+    pub timeout: Option<Duration>,
+    pub replay: Option<ScriptedEvents>,
 }
 
 impl Config {
     fn new(args: Args) -> Self {
-        let mut border = vec!['┌', '─', '┐', '│', '└', '┘'];
+        let mut border: Option<Border> = None;
         let mut center = false;
         let mut stretch = false;
-        let mut position = crossterm::cursor::position().expect("Could not get cursor position");
+        let mut position: Option<(u16, u16)> = None;
         let mut query: Vec<String> = Vec::new();
-        let mut length = 8;
+        let mut spec: Option<FormSpec> = None;
+        let mut length: Option<u16> = None;
+        let mut max_width: Option<u16> = None;
+        let mut mask = Echo::Mask('*');
+        let mut rows: Option<u16> = None;
+        let mut submit_key: Option<KeyEvent> = None;
+        let mut output = Format::Lines;
+        let mut null = false;
+        let mut pick = false;
+        let mut confirm = false;
+        let mut msgbox = false;
+        let mut timeout: Option<Duration> = None;
+        let mut replay: Option<ScriptedEvents> = None;
         let mut finished = false;
 
-        for arg in args.skip(1) {
+        let args: Vec<String> = args.skip(1).collect();
+        // Errors are reported before every option is parsed, so look for --confirm first.
+        let error_code = if args
+            .iter()
+            .take_while(|arg| arg.starts_with('-') && *arg != "--")
+            .any(|arg| arg.trim_start_matches('-') == "confirm")
+        {
+            EXIT_CONFIRM_ERROR
+        } else {
+            EXIT_ERROR
+        };
+
+        for arg in args {
             if !finished {
                 if arg == "--" {
                     finished = true;
@@ -36,32 +70,91 @@ impl Config {
                     let trimmed = arg.trim_start_matches('-');
                     if trimmed.contains('=') {
                         if let Some(stripped) = trimmed.strip_prefix("b=") {
-                            match stripped {
-                                "single" => (),
-                                "double" => border = vec!['╔', '═', '╗', '║', '╚', '╝'],
-                                "thick" => border = vec!['┏', '━', '┓', '┃', '┗', '┛'],
-                                "curved" => border = vec!['╭', '─', '╮', '│', '╰', '╯'],
+                            match Border::parse(stripped) {
+                                Ok(b) => border = Some(b),
+                                Err(e) => error(&e, error_code),
+                            }
+                            continue;
+                        } else if let Some(stripped) = trimmed.strip_prefix("form=") {
+                            match FormSpec::load(stripped) {
+                                Ok(s) => spec = Some(s),
+                                Err(e) => error(&e, error_code),
+                            }
+                            continue;
+                        } else if let Some(stripped) = trimmed.strip_prefix("l=") {
+                            length = Some(stripped.parse::<u16>().unwrap_or(8));
+                            continue;
+                        } else if let Some(stripped) = trimmed.strip_prefix("m=") {
+                            let mut chars = stripped.chars();
+                            mask = match (chars.next(), chars.next()) {
+                                (None, _) => Echo::Hidden,
+                                (Some(c), None) => Echo::Mask(c),
                                 _ => {
-                                    border = stripped.chars().collect::<Vec<char>>();
-                                    if border.len() != 6 {
-                                        error(&format!("Invalid border length: {}", border.len()));
-                                    }
+                                    error("Mask must be a single character or empty", error_code);
+                                    continue;
                                 }
+                            };
+                            continue;
+                        } else if let Some(stripped) = trimmed
+                            .strip_prefix("o=")
+                            .or_else(|| trimmed.strip_prefix("output="))
+                        {
+                            match Format::parse(stripped) {
+                                Some(format) => output = format,
+                                None => error(
+                                    &format!("Invalid output format: {}", stripped),
+                                    error_code,
+                                ),
                             }
                             continue;
-                        } else if let Some(stripped) = trimmed.strip_prefix("l=") {
-                            length = stripped.parse::<u16>().unwrap_or(8);
+                        } else if let Some(stripped) = trimmed.strip_prefix("w=") {
+                            match stripped.parse::<u16>() {
+                                Ok(w) => max_width = Some(w),
+                                Err(_) => {
+                                    error(&format!("Invalid width: {}", stripped), error_code)
+                                }
+                            }
+                            continue;
+                        } else if let Some(stripped) = trimmed.strip_prefix("rows=") {
+                            match stripped.parse::<u16>() {
+                                Ok(r) if r > 0 => rows = Some(r),
+                                _ => error(
+                                    &format!("Invalid number of rows: {}", stripped),
+                                    error_code,
+                                ),
+                            }
+                            continue;
+                        } else if let Some(stripped) = trimmed.strip_prefix("submit=") {
+                            match parse_key(stripped) {
+                                Some(key) => submit_key = Some(key),
+                                None => error(&format!("Invalid key: {}", stripped), error_code),
+                            }
+                            continue;
+                        } else if let Some(stripped) = trimmed.strip_prefix("timeout=") {
+                            match stripped.parse::<f64>().map(Duration::try_from_secs_f64) {
+                                Ok(Ok(t)) => timeout = Some(t),
+                                _ => error(&format!("Invalid timeout: {}", stripped), error_code),
+                            }
+                            continue;
+                        } else if let Some(stripped) = trimmed.strip_prefix("replay=") {
+                            match fs::read_to_string(stripped)
+                                .map_err(|e| format!("Could not read {}: {}", stripped, e))
+                                .and_then(|script| ScriptedEvents::parse(&script))
+                            {
+                                Ok(events) => replay = Some(events),
+                                Err(e) => error(&e, error_code),
+                            }
                             continue;
                         } else if let Some(stripped) = trimmed.strip_prefix("p=") {
                             if let Some((x, y)) = stripped.split_once(',') {
                                 if let Ok(x) = x.parse::<u16>() {
                                     if let Ok(y) = y.parse::<u16>() {
-                                        position = (x, y);
+                                        position = Some((x, y));
                                         continue;
                                     }
                                 }
                             }
-                            error("Invalid position");
+                            error("Invalid position", error_code);
                         }
                     } else {
                         match trimmed {
@@ -73,13 +166,29 @@ impl Config {
                                 stretch = true;
                                 continue;
                             }
+                            "0" | "null" => {
+                                null = true;
+                                continue;
+                            }
+                            "pick" => {
+                                pick = true;
+                                continue;
+                            }
+                            "confirm" => {
+                                confirm = true;
+                                continue;
+                            }
+                            "msgbox" => {
+                                msgbox = true;
+                                continue;
+                            }
                             "h" => {
                                 print_help();
                                 continue;
                             }
                             _ => {
                                 eprintln!("Invalid argument: {}", arg);
-                                exit(1);
+                                exit(error_code);
                             }
                         }
                     }
@@ -91,25 +200,109 @@ impl Config {
             query.push(arg);
         }
 
-        if query.is_empty() {
-            error("No query specified");
+        let form = match spec {
+            Some(_) if pick => Err("Cannot combine --form with --pick".to_owned()),
+            _ if pick && confirm => Err("Cannot combine --pick with --confirm".to_owned()),
+            _ if msgbox && (pick || confirm) => {
+                Err("Cannot combine --msgbox with --pick or --confirm".to_owned())
+            }
+            _ if timeout.is_some() && !msgbox => Err("--timeout requires --msgbox".to_owned()),
+            Some(_) if !query.is_empty() => Err("Cannot combine --form with QUERY".to_owned()),
+            Some(spec) if confirm && spec.has_fields() => {
+                Err("--confirm only shows the labels of --form".to_owned())
+            }
+            Some(spec) if msgbox && spec.has_fields() => {
+                Err("--msgbox only shows the labels of --form".to_owned())
+            }
+            Some(spec) => spec.into_form(mask, rows.unwrap_or(4)),
+            None if query.is_empty() => Err("No query specified".to_owned()),
+            // Every line of a message box or a question is shown as it is, even if it
+            // looks like a field, as only the buttons are answered.
+            None if msgbox || confirm => Ok(query
+                .iter()
+                .skip(1)
+                .fold(Form::new(query[0].clone()), |form, q| form.label(q.clone()))),
+            None => query
+                .iter()
+                .skip(1)
+                .try_fold(Form::new(query[0].clone()), |form, q| {
+                    Line::parse(q, mask, rows.unwrap_or(4)).map(|line| form.line(line))
+                }),
+        };
+        let form = match form {
+            Ok(form) if pick => read_choices().map(|choices| {
+                let mut field = Field::pick(choices).name(query[0].clone());
+                if let Some(rows) = rows {
+                    field = field.rows(rows);
+                }
+                form.field("> ", field)
+            }),
+            Ok(form) if confirm => Ok(form.field(
+                "",
+                Field::buttons([("yes", "Yes"), ("no", "No")]).name("confirm"),
+            )),
+            form => form,
+        };
+        let mut form = match form {
+            Ok(form) => form.center(center).stretch(stretch),
+            Err(e) => {
+                error(&e, error_code);
+                exit(error_code);
+            }
+        };
+        if let Some(border) = border {
+            form = form.border(border);
+        }
+        if let Some(length) = length {
+            form = form.length(length);
+        }
+        if let Some(max_width) = max_width {
+            form = form.max_width(max_width);
+        }
+        if let Some(key) = submit_key {
+            form = form.submit_key(key);
+        }
+        if let Some((x, y)) = position {
+            form = form.position(x, y);
         }
 
         Self {
-            border,
-            center,
-            stretch,
-            position,
-            query,
-            length,
+            form,
+            output,
+            null,
+            pick,
+            confirm,
+            msgbox,
+            timeout,
+            replay,
         }
     }
 }
 
-fn error(msg: &str) {
+/// Reads the choices of `--pick` from stdin, one per line.
+fn read_choices() -> Result<Vec<Choice>, String> {
+    if stdin().is_terminal() {
+        return Err("--pick reads its choices from stdin".to_owned());
+    }
+    let mut content = String::new();
+    stdin()
+        .read_to_string(&mut content)
+        .map_err(|e| format!("Could not read choices from stdin: {}", e))?;
+    let choices: Vec<Choice> = content
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| Choice::new(line, line))
+        .collect();
+    if choices.is_empty() {
+        return Err("No choices given on stdin".to_owned());
+    }
+    Ok(choices)
+}
+
+fn error(msg: &str, code: i32) {
     eprintln!("error: {}", msg);
     print_help();
-    exit(1);
+    exit(code);
 }
 
 fn print_help() {
@@ -117,165 +310,182 @@ fn print_help() {
         "Usage: ibox [OPTION]... [QUERY]...\n",
         "Search for QUERY in FILES.\n",
         "Example:\n",
-        "    ibox -l=24 'Title' 'Context' 'Question: ?>'\n",
+        "    ibox -l=24 'Title' 'Context' 'Question: ?>' 'Password: *>'\n",
+        "Lines ending in ?> are input fields, lines ending in *> are masked input fields.\n",
+        "Lines ending in #> are multi-line input fields.\n",
+        "Lines ending in @> are lists of two or more options separated by |, written as\n",
+        "VALUE=LABEL or just VALUE, of which the chosen option's VALUE is the answer:\n",
+        "    'Env: @>dev=Development|prod=Production'\n",
+        "Lines ending in %> are checklists of options, answered with every checked VALUE.\n",
+        "Fields may be followed by a [default] value and a (placeholder):\n",
+        "    'Name: ?>[alice](your name)'\n",
+        "and {{validator}} groups: {{nonempty}}, {{int:MIN..MAX}}, {{max:LENGTH}} or {{re:REGEX}}:\n",
+        "    'Age: ?>{{nonempty}}{{int:0..150}}'\n",
+        "Empty fields only fail {{nonempty}}, so other validators leave them optional.\n",
+        "Fields may be prefixed with NAME= to name them in structured output:\n",
+        "    'user=Username: ?>'\n",
         "Options:\n",
         "    -b=BORDER\n",
         "        Specify the border characters or presets.\n",
         "        Presets: single (default), double, thick, curved\n",
         "        Default: ┌─┐│└┘\n",
+        "    --form=PATH\n",
+        "        Load the box from a TOML or JSON form definition instead of QUERY.\n",
+        "        A PATH of - reads the definition from stdin.\n",
+        "        With --msgbox or --confirm, the definition may only contain labels.\n",
         "    -l=LENGTH\n",
         "        Specify the added length of the input space after the longest line.\n",
         "        Default: 8\n",
+        "    -m=MASK\n",
+        "        Specify the character shown in place of input in masked fields.\n",
+        "        An empty MASK hides the input entirely.\n",
+        "        Default: *\n",
+        "    -o=FORMAT, --output=FORMAT\n",
+        "        Specify how the answers are printed.\n",
+        "        Formats: lines (default), json, shell\n",
+        "        json prints an object keyed by each field's name or label, where\n",
+        "        repeated names get a _2, _3, ... suffix.\n",
+        "        shell prints NAME='value' assignments for use with eval. Names of\n",
+        "        special variables such as PATH, IFS, HOME or PS1 get a leading _.\n",
+        "        Checklists print each checked value as its own answer in lines,\n",
+        "        an array in json and newline separated values in shell.\n",
+        "    -0, --null\n",
+        "        Terminate each answer with NUL instead of a newline.\n",
+        "    --pick\n",
+        "        Read choices from stdin, one per line, and pick one of them by typing to\n",
+        "        fuzzily filter them. The box is drawn on the terminal, and QUERY is its\n",
+        "        title followed by any other lines.\n",
+        "        Example: ls | ibox --pick 'Choose file'\n",
         "    -p=X,Y\n",
         "        Specify the position of the top left corner of the box.\n",
         "        Default: current cursor position\n",
         "    -c\n",
         "        Center the box on the screen.\n",
+        "    --confirm\n",
+        "        Ask a yes or no question with Yes and No buttons after the QUERY lines,\n",
+        "        answered through the exit status instead of printing any answers.\n",
+        "        Lines are shown as they are, without fields.\n",
+        "        Example: if ibox --confirm 'Deploy?'; then ...\n",
+        "    --msgbox\n",
+        "        Show the QUERY lines as a message with an OK button, and clear the box\n",
+        "        once any key is pressed. Lines are shown as they are, without fields.\n",
+        "        Example: ibox --msgbox --timeout=5 'Backup' 'Backup finished.'\n",
+        "    --replay=FILE\n",
+        "        Read keys from FILE instead of the terminal, one per line.\n",
+        "        Keys are named like a, Enter, Tab or Ctrl-W; other lines are typed out.\n",
+        "    --rows=ROWS\n",
+        "        Specify the number of rows of multi-line fields and lists.\n",
+        "        Default: 4, or 10 for --pick\n",
+        "    --timeout=SECONDS\n",
+        "        Clear the box of --msgbox after SECONDS if no key was pressed.\n",
+        "        Default: wait for a key\n",
         "    -s\n",
         "        Makes the box stretch to the terminal's sides.\n",
+        "    --submit=KEY\n",
+        "        Specify the key that leaves a multi-line field, named like in --replay.\n",
+        "        Default: Alt-Enter\n",
+        "    -w=WIDTH\n",
+        "        Specify the maximum width of the box. Longer lines are wrapped.\n",
+        "        Default: terminal width\n",
         "    -h\n",
         "        Print this help message and exit.\n",
+        "Keys:\n",
+        "    Left/Right, Home/End, Ctrl-A/Ctrl-E\n",
+        "        Move the cursor within the field.\n",
+        "    Ctrl-Left/Ctrl-Right, Alt-B/Alt-F\n",
+        "        Move the cursor by word.\n",
+        "    Backspace/Delete, Ctrl-W, Ctrl-U/Ctrl-K\n",
+        "        Delete a character, the previous word, or up to the start/end of the field.\n",
+        "    Tab/Shift-Tab, Up/Down\n",
+        "        Move to the next/previous field.\n",
+        "    Enter\n",
+        "        Move to the next field, or submit the answers on the last field.\n",
+        "        Fields must pass their validators before moving on.\n",
+        "        In multi-line fields, Enter starts a new line and Up/Down move between\n",
+        "        lines; the --submit key moves on instead.\n",
+        "    Up/Down, j/k, Home/End in lists\n",
+        "        Move between options. Enter chooses the highlighted option.\n",
+        "    Up/Down with --pick\n",
+        "        Move between matching choices. Other keys edit the filter.\n",
+        "    Left/Right, Tab/Shift-Tab, Y/N with --confirm\n",
+        "        Choose between Yes and No. Y and N also answer right away.\n",
+        "    Space in checklists\n",
+        "        Check or uncheck the highlighted option.\n",
+        "    Any key with --msgbox\n",
+        "        Clear the box of the message.\n",
+        "    Ctrl-S\n",
+        "        Submit the answers from any field.\n",
+        "    Esc, Ctrl-C, Ctrl-D on an empty field\n",
+        "        Clear the box and exit without printing any answers.\n",
+        "Exit status:\n",
+        "    0   Answers were submitted, Yes was chosen with --confirm, or the box of\n",
+        "        --msgbox was dismissed or timed out.\n",
+        "    1   Invalid arguments, or the box could not be drawn.\n",
+        "        With --confirm, No was chosen instead.\n",
+        "    2   Invalid arguments, or the box could not be drawn, with --confirm.\n",
+        "    130 The prompt was cancelled.\n",
     ));
 }
 
-fn main() {
-    let config = Config::new(env::args());
-    let border = config.border;
-    let query = config.query;
-    let stderr = &mut stderr();
-    let mut positions: Vec<(u16, u16)> = Vec::new();
-
-    let stretch = config.stretch;
-    let length = if stretch {
-        let (width, _) = crossterm::terminal::size().expect("Could not get terminal size");
-        width - 3
-    } else {
-        query
-            .iter()
-            .map(|q| q.len() as u16 - q.ends_with("?>") as u16 * 2)
-            .max()
-            .unwrap()
-            + config.length
-    };
-
-    let center = config.center;
-    let (mut sx, sy) = if center {
-        let (sx, sy) = crossterm::terminal::size().expect("Failed to get terminal size");
-        (sx / 2 - length / 2 - 2, sy / 2 - query.len() as u16 / 2)
-    } else {
-        config.position
-    };
-
-    if stretch {
-        sx = 0;
+fn run<W: Write>(
+    form: Form,
+    mut backend: CrosstermBackend<W>,
+    replay: Option<ScriptedEvents>,
+) -> Result<Answers, Error> {
+    match replay {
+        Some(mut events) => form.run_with(&mut backend, &mut events),
+        None => form.run_with(&mut backend, &mut TerminalEvents),
     }
-    let sx = sx;
-    cursor(stderr, sx, sy);
-
-    let mut sy = sy + 1;
-
-    eprintln!("{}", top(query.get(0), &border, length));
-
-    if query.len() > 1 {
-        for q in query.iter().skip(1) {
-            cursor(stderr, sx, sy);
-            let (text, new_pos) = mid(q, &border, length);
-            if let Some(new_pos) = new_pos {
-                positions.push(new_pos);
-            }
-            eprintln!("{}", text);
-            sy += 1;
-        }
-    }
-    cursor(stderr, sx, sy);
-
-    eprintln!("{}", bot(&border, length));
-
-    let mut input = String::new();
-    for (x, y) in positions {
-        let (ex, ey) = crossterm::cursor::position().expect("Failed to get cursor position");
-        loop {
-            force_cursor(stderr, x, y);
-            match read().expect("Failed to read input") {
-                Event::Key(event) => {
-                    if event.code == crossterm::event::KeyCode::Enter {
-                        break;
-                    }
-                    if let KeyCode::Char(c) = event.code {
-                        input.push(c);
-                    }
-                }
-                _ => break,
-            }
-        }
-
-        cursor(stderr, ex, ey);
-        input.push('\n');
-    }
-
-    print!("{}", input);
 }
 
-fn top(title: Option<&String>, border: &[char], length: u16) -> String {
-    let mut top = String::new();
-    top.push(border[0]);
-    top.push(border[1]);
-    if let Some(title) = title {
-        top.push_str(title);
-        for _ in 0..(length - title.len() as u16) {
-            top.push(border[1]);
-        }
+fn show<W: Write>(
+    form: Form,
+    mut backend: CrosstermBackend<W>,
+    replay: Option<ScriptedEvents>,
+    timeout: Option<Duration>,
+) -> Result<Answers, Error> {
+    match replay {
+        Some(mut events) => form.show_with(&mut backend, &mut events, timeout),
+        None => form.show_with(&mut backend, &mut TerminalEvents, timeout),
     }
-
-    top.push(border[2]);
-    top
+    .map(|()| Answers::default())
 }
 
-fn mid(text: &String, border: &[char], length: u16) -> (String, Option<(u16, u16)>) {
-    let mut mid = String::new();
-    mid.push(border[3]);
-    if let Some(question) = text.strip_suffix("?>") {
-        if let Ok((x, y)) = crossterm::cursor::position() {
-            let question_len = question.len() as u16;
-            mid.push_str(question);
-            for _ in 0..(length - question_len + 1) {
-                mid.push(' ');
-            }
-            mid.push(border[3]);
-            return (mid, Some((x + 1 + question_len as u16, y)));
+fn main() {
+    let config = Config::new(env::args());
+    // Draw on the terminal itself when picking, as stdin holds the choices.
+    let result = if config.pick {
+        match OpenOptions::new().write(true).open("/dev/tty") {
+            Ok(tty) => run(config.form, CrosstermBackend::new(tty), config.replay),
+            Err(e) => Err(Error::Io(e)),
         }
-
-        error("Cannot get cursor position");
-        exit(1);
+    } else if config.msgbox {
+        let backend = CrosstermBackend::new(stderr());
+        show(config.form, backend, config.replay, config.timeout)
     } else {
-        mid.push_str(text);
-        for _ in 0..(length - text.len() as u16 + 1) {
-            mid.push(' ');
+        run(config.form, CrosstermBackend::new(stderr()), config.replay)
+    };
+    let answers = match result {
+        Ok(answers) => answers,
+        Err(Error::Cancelled) => exit(EXIT_CANCELLED),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            exit(if config.confirm {
+                EXIT_CONFIRM_ERROR
+            } else {
+                EXIT_ERROR
+            });
         }
-        mid.push(border[3]);
-        (mid, None)
-    }
-}
+    };
 
-fn bot(border: &[char], length: u16) -> String {
-    let mut bot = String::new();
-    bot.push(border[4]);
-    bot.push(border[1]);
-    for _ in 0..length {
-        bot.push(border[1]);
+    // The buttons are the last field, whatever name they were given to keep it unique.
+    if config.confirm {
+        match answers.iter().last() {
+            Some((_, Value::Text(answer))) if answer == "yes" => exit(0),
+            _ => exit(EXIT_NO),
+        }
     }
-    bot.push(border[5]);
-    bot
-}
-
-fn cursor(stderr: &mut Stderr, x: u16, y: u16) {
-    stderr
-        .queue(crossterm::cursor::MoveTo(x, y))
-        .expect("Failed to move cursor");
-}
 
-fn force_cursor(stderr: &mut Stderr, x: u16, y: u16) {
-    stderr
-        .execute(crossterm::cursor::MoveTo(x, y))
-        .expect("Failed to move cursor");
+    let terminator = if config.null { '\0' } else { '\n' };
+    print!("{}", answers.format(config.output, terminator));
 }
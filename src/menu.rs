@@ -0,0 +1,175 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{field::Choice, output::Value};
+
+/// A list of options with a cursor, scrolled so that the cursor stays visible.
+/// `top` is the first visible option, and `checked` holds which options are
+/// checked if any number of them may be.
+pub struct Menu {
+    options: Vec<Choice>,
+    cursor: usize,
+    top: usize,
+    checked: Option<Vec<bool>>,
+}
+
+impl Menu {
+    /// Creates a menu with the cursor on the option whose value is `default`, if any.
+    /// If `multiple` is set, options are checked with Space rather than chosen.
+    pub fn new(options: Vec<Choice>, default: &str, multiple: bool) -> Self {
+        let cursor = options
+            .iter()
+            .position(|option| option.value == default)
+            .unwrap_or_default();
+        Self {
+            checked: multiple.then(|| vec![false; options.len()]),
+            options,
+            cursor,
+            top: 0,
+        }
+    }
+
+    /// Returns the value of the option under the cursor,
+    /// or of every checked option if any number of them may be.
+    pub fn value(&self) -> Value {
+        match &self.checked {
+            Some(checked) => Value::List(
+                self.options
+                    .iter()
+                    .zip(checked)
+                    .filter(|(_, &checked)| checked)
+                    .map(|(option, _)| option.value.clone())
+                    .collect(),
+            ),
+            None => Value::Text(
+                self.options
+                    .get(self.cursor)
+                    .map(|option| option.value.clone())
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.options.is_empty()
+    }
+
+    /// Returns the labels of the options visible in the given number of rows,
+    /// each prefixed with a marker, and the row of the cursor within them.
+    pub fn view(&mut self, height: usize) -> (Vec<String>, usize) {
+        let height = height.max(1);
+        if self.cursor < self.top {
+            self.top = self.cursor;
+        } else if self.cursor >= self.top + height {
+            self.top = self.cursor + 1 - height;
+        }
+
+        let rows = self
+            .options
+            .iter()
+            .enumerate()
+            .skip(self.top)
+            .take(height)
+            .map(|(i, option)| {
+                let marker = match &self.checked {
+                    Some(checked) if checked[i] => "[x]",
+                    Some(_) => "[ ]",
+                    None if i == self.cursor => ">",
+                    None => " ",
+                };
+                format!("{} {}", marker, option.label)
+            })
+            .collect();
+
+        (rows, self.cursor - self.top)
+    }
+
+    /// Moves the cursor with the arrow keys, j/k and Home/End, and checks options with Space.
+    /// Returns false if the key is not a menu key or the cursor cannot move further.
+    pub fn handle(&mut self, event: KeyEvent) -> bool {
+        let last = self.options.len().saturating_sub(1);
+        match event.code {
+            KeyCode::Up | KeyCode::Char('k') if self.cursor > 0 => self.cursor -= 1,
+            KeyCode::Down | KeyCode::Char('j') if self.cursor < last => self.cursor += 1,
+            KeyCode::Home | KeyCode::Char('g') => self.cursor = 0,
+            KeyCode::End | KeyCode::Char('G') => self.cursor = last,
+            KeyCode::Char(' ') => match &mut self.checked {
+                Some(checked) if !checked.is_empty() => {
+                    checked[self.cursor] = !checked[self.cursor];
+                }
+                _ => return false,
+            },
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+/// A row of buttons, one of which is chosen.
+pub struct Buttons {
+    options: Vec<Choice>,
+    cursor: usize,
+}
+
+impl Buttons {
+    /// Creates buttons with the one whose value is `default` chosen, if any.
+    pub fn new(options: Vec<Choice>, default: &str) -> Self {
+        let cursor = options
+            .iter()
+            .position(|option| option.value == default)
+            .unwrap_or_default();
+        Self { options, cursor }
+    }
+
+    /// Returns the value of the chosen button.
+    pub fn value(&self) -> Value {
+        Value::Text(
+            self.options
+                .get(self.cursor)
+                .map(|option| option.value.clone())
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Returns the label of each button and whether it is chosen.
+    pub fn view(&self) -> impl Iterator<Item = (String, bool)> + '_ {
+        self.options
+            .iter()
+            .enumerate()
+            .map(|(i, option)| (format!("[ {} ]", option.label), i == self.cursor))
+    }
+
+    /// Chooses the button whose label starts with the given character, ignoring case.
+    /// Returns false if there is none.
+    pub fn press(&mut self, c: char) -> bool {
+        let found = self.options.iter().position(|option| {
+            option
+                .label
+                .chars()
+                .next()
+                .is_some_and(|first| first.to_lowercase().eq(c.to_lowercase()))
+        });
+        match found {
+            Some(i) => self.cursor = i,
+            None => return false,
+        }
+        true
+    }
+
+    /// Moves between buttons with Left/Right, h/l and Tab/Shift-Tab, wrapping around.
+    /// Returns false if the key is not a button key.
+    pub fn handle(&mut self, event: KeyEvent) -> bool {
+        let len = self.options.len().max(1);
+        match event.code {
+            KeyCode::Left | KeyCode::BackTab | KeyCode::Char('h') => {
+                self.cursor = (self.cursor + len - 1) % len;
+            }
+            KeyCode::Right | KeyCode::Tab | KeyCode::Char('l') => {
+                self.cursor = (self.cursor + 1) % len;
+            }
+            _ => return false,
+        }
+
+        true
+    }
+}
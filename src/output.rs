@@ -0,0 +1,274 @@
+/// How the answers are printed.
+#[derive(Clone, Copy)]
+pub enum Format {
+    Lines,
+    Json,
+    Shell,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "lines" => Some(Self::Lines),
+            "json" => Some(Self::Json),
+            "shell" => Some(Self::Shell),
+            _ => None,
+        }
+    }
+}
+
+/// The answer to a field: its text, or the values checked in a checklist.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    Text(String),
+    List(Vec<String>),
+}
+
+/// The values entered into a form's fields, in order.
+#[derive(Clone, Debug, Default)]
+pub struct Answers {
+    answers: Vec<(String, Value)>,
+}
+
+impl Answers {
+    pub(crate) fn push(&mut self, name: String, value: Value) {
+        self.answers.push((name, value));
+    }
+
+    /// Returns the text of the first field with the given name,
+    /// or `None` if it is a checklist.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        match self.value(name)? {
+            Value::Text(text) => Some(text),
+            Value::List(_) => None,
+        }
+    }
+
+    /// Returns the value of the first field with the given name.
+    pub fn value(&self, name: &str) -> Option<&Value> {
+        self.iter()
+            .find(|(field, _)| *field == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Iterates over (name, value) pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.answers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.answers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.answers.is_empty()
+    }
+
+    /// Formats the answers, ending each answer (or the JSON object) with `terminator`.
+    pub fn format(&self, format: Format, terminator: char) -> String {
+        format_answers(format, &self.answers, terminator)
+    }
+}
+
+/// Formats the answers. Checklists print each value as its own answer in `lines`,
+/// an array in `json` and newline separated values in `shell`, which has no arrays.
+fn format_answers(format: Format, answers: &[(String, Value)], terminator: char) -> String {
+    match format {
+        Format::Lines => answers
+            .iter()
+            .flat_map(|(_, value)| match value {
+                Value::Text(text) => std::slice::from_ref(text),
+                Value::List(values) => values.as_slice(),
+            })
+            .map(|value| format!("{}{}", value, terminator))
+            .collect(),
+        Format::Json => {
+            let entries: Vec<String> = answers
+                .iter()
+                .map(|(name, value)| format!("{}:{}", json_string(name), json_value(value)))
+                .collect();
+            format!("{{{}}}{}", entries.join(","), terminator)
+        }
+        Format::Shell => {
+            let mut names: Vec<String> = Vec::new();
+            answers
+                .iter()
+                .map(|(name, value)| {
+                    let value = match value {
+                        Value::Text(text) => shell_quote(text),
+                        Value::List(values) => shell_quote(&values.join("\n")),
+                    };
+                    // Names such as `a-b` and `a_b` map to the same variable, so suffix
+                    // the later ones rather than overwriting the earlier answers.
+                    let base = shell_name(name);
+                    let mut name = base.clone();
+                    let mut suffix = 1;
+                    while names.contains(&name) {
+                        suffix += 1;
+                        name = format!("{}_{}", base, suffix);
+                    }
+                    names.push(name.clone());
+                    format!("{}={}{}", name, value, terminator)
+                })
+                .collect()
+        }
+    }
+}
+
+fn json_value(value: &Value) -> String {
+    match value {
+        Value::Text(text) => json_string(text),
+        Value::List(values) => {
+            let values: Vec<String> = values.iter().map(|value| json_string(value)).collect();
+            format!("[{}]", values.join(","))
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    // Serializing a string cannot fail.
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+/// Variables that change how the shell or the programs it runs behave.
+const SPECIAL_NAMES: [&str; 22] = [
+    "CDPATH",
+    "ENV",
+    "EUID",
+    "FPATH",
+    "GLOBIGNORE",
+    "HISTFILE",
+    "HOME",
+    "IFS",
+    "LANG",
+    "MAIL",
+    "MAILPATH",
+    "OLDPWD",
+    "OPTARG",
+    "OPTIND",
+    "PATH",
+    "PPID",
+    "PROMPT_COMMAND",
+    "PWD",
+    "SHELL",
+    "SHELLOPTS",
+    "TMPDIR",
+    "UID",
+];
+
+/// Prefixes of families of special variables, such as `PS1` or `LD_PRELOAD`.
+const SPECIAL_PREFIXES: [&str; 4] = ["BASH", "LC_", "LD_", "PS"];
+
+/// Turns a field name into a valid shell variable name that does not
+/// overwrite a special variable when evaluated.
+fn shell_name(name: &str) -> String {
+    let mut out: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let special = SPECIAL_NAMES.contains(&out.as_str())
+        || SPECIAL_PREFIXES
+            .iter()
+            .any(|prefix| out.starts_with(prefix))
+        || out == "LANG";
+    if special || !out.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        out.insert(0, '_');
+    }
+    out
+}
+
+/// Single-quotes a value for POSIX shells.
+/// Everything but `'` is literal inside single quotes, including newlines.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::*;
+
+    fn text(name: &str, value: &str) -> (String, Value) {
+        (name.to_owned(), Value::Text(value.to_owned()))
+    }
+
+    /// Evaluates the assignments in `sh` and prints the variable back.
+    fn eval(assignments: &str, name: &str) -> String {
+        let script = format!("{}\nprintf %s \"${}\"", assignments, name);
+        let output = Command::new("sh").arg("-c").arg(script).output().unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    #[test]
+    fn shell_quote_escapes_single_quotes() {
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(eval(&format!("x={}", shell_quote("it's")), "x"), "it's");
+    }
+
+    #[test]
+    fn shell_quote_keeps_newlines() {
+        assert_eq!(shell_quote("a\nb"), "'a\nb'");
+        assert_eq!(eval(&format!("x={}", shell_quote("a\nb")), "x"), "a\nb");
+    }
+
+    #[test]
+    fn shell_quote_does_not_expand() {
+        for value in ["$(echo x)", "`echo x`", "$HOME", "a; b", "\\\"*"] {
+            assert_eq!(eval(&format!("x={}", shell_quote(value)), "x"), value);
+        }
+    }
+
+    #[test]
+    fn shell_name_replaces_invalid_characters() {
+        assert_eq!(shell_name("user name"), "user_name");
+        assert_eq!(shell_name("a-b"), "a_b");
+        assert_eq!(shell_name("1st"), "_1st");
+        assert_eq!(shell_name("$(x)"), "__x_");
+    }
+
+    #[test]
+    fn shell_name_avoids_special_variables() {
+        for name in [
+            "PATH",
+            "IFS",
+            "HOME",
+            "PS1",
+            "PS4",
+            "LD_PRELOAD",
+            "BASH_ENV",
+            "LANG",
+        ] {
+            assert_eq!(shell_name(name), format!("_{}", name));
+        }
+        assert_eq!(shell_name("path"), "path");
+        assert_eq!(shell_name("HOMEPAGE"), "HOMEPAGE");
+
+        let answers = [text("PATH", "/tmp"), text("_PATH", "x")];
+        let output = format_answers(Format::Shell, &answers, '\n');
+        assert_eq!(output, "_PATH='/tmp'\n_PATH_2='x'\n");
+        assert_ne!(eval(&output, "PATH"), "/tmp");
+    }
+
+    #[test]
+    fn shell_names_do_not_clash() {
+        let answers = [text("a-b", "1"), text("a_b", "2"), text("a b", "3")];
+        let output = format_answers(Format::Shell, &answers, '\n');
+        assert_eq!(output, "a_b='1'\na_b_2='2'\na_b_3='3'\n");
+        assert_eq!(eval(&output, "a_b"), "1");
+        assert_eq!(eval(&output, "a_b_2"), "2");
+    }
+
+    #[test]
+    fn shell_lists_are_joined_with_newlines() {
+        let answers = [(
+            "x".to_owned(),
+            Value::List(vec!["a'b".to_owned(), "c".to_owned()]),
+        )];
+        let output = format_answers(Format::Shell, &answers, '\n');
+        assert_eq!(eval(&output, "x"), "a'b\nc");
+    }
+}
@@ -0,0 +1,65 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{editor::LineEditor, field::Choice, fuzzy, menu::Menu, output::Value};
+
+/// A filter and the options that match it, with a cursor over the matches.
+pub struct Picker {
+    options: Vec<Choice>,
+    pub filter: LineEditor,
+    pub menu: Menu,
+}
+
+impl Picker {
+    pub fn new(options: Vec<Choice>, filter: &str) -> Self {
+        let mut picker = Self {
+            options,
+            filter: LineEditor::with_text(filter),
+            menu: Menu::new(Vec::new(), "", false),
+        };
+        picker.update();
+        picker
+    }
+
+    /// Returns the value of the match under the cursor.
+    pub fn value(&self) -> Value {
+        self.menu.value()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filter.is_empty()
+    }
+
+    /// Fails if no option matches the filter.
+    pub fn check(&self) -> Result<(), String> {
+        if self.menu.is_empty() {
+            return Err("No matching options".to_owned());
+        }
+        Ok(())
+    }
+
+    /// Moves between matches with Up and Down, and edits the filter with other keys.
+    /// Returns false if the key is neither or the cursor cannot move further.
+    pub fn handle(&mut self, event: KeyEvent) -> bool {
+        match event.code {
+            KeyCode::Up | KeyCode::Down => self.menu.handle(event),
+            _ => {
+                let text = self.filter.text();
+                let handled = self.filter.handle(event);
+                if self.filter.text() != text {
+                    self.update();
+                }
+                handled
+            }
+        }
+    }
+
+    /// Ranks the options by how well they match the filter, moving the cursor to the best.
+    fn update(&mut self) {
+        let filter = self.filter.text();
+        let matches = fuzzy::rank(&filter, self.options.iter().map(|o| o.label.as_str()))
+            .into_iter()
+            .map(|i| self.options[i].clone())
+            .collect();
+        self.menu = Menu::new(matches, "", false);
+    }
+}
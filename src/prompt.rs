@@ -0,0 +1,211 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    editor::TextArea,
+    field::{Echo, Field, Kind},
+    menu::{Buttons, Menu},
+    output::Value,
+    picker::Picker,
+    validate::Validator,
+};
+
+/// What a field is filled in with.
+pub enum Widget {
+    Text(TextArea),
+    Menu(Menu),
+    Pick(Picker),
+    Buttons(Buttons),
+}
+
+impl Widget {
+    pub fn value(&self) -> Value {
+        match self {
+            Self::Text(editor) => Value::Text(editor.text()),
+            Self::Menu(menu) => menu.value(),
+            Self::Pick(picker) => picker.value(),
+            Self::Buttons(buttons) => buttons.value(),
+        }
+    }
+
+    /// Returns the text that validators check, which for checklists
+    /// is every checked value on its own line.
+    pub fn text(&self) -> String {
+        match self.value() {
+            Value::Text(text) => text,
+            Value::List(values) => values.join("\n"),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Text(editor) => editor.is_empty(),
+            Self::Menu(_) | Self::Buttons(_) => false,
+            Self::Pick(picker) => picker.is_empty(),
+        }
+    }
+
+    pub fn handle(&mut self, event: KeyEvent) -> bool {
+        match self {
+            Self::Text(editor) => editor.handle(event),
+            Self::Menu(menu) => menu.handle(event),
+            Self::Pick(picker) => picker.handle(event),
+            Self::Buttons(buttons) => buttons.handle(event),
+        }
+    }
+}
+
+/// A field placed in the box, along with its contents.
+pub struct Input {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+    pub name: String,
+    pub echo: Echo,
+    pub placeholder: String,
+    pub validators: Vec<Validator>,
+    pub widget: Widget,
+}
+
+impl Input {
+    pub fn new(x: u16, y: u16, width: u16, spec: Field) -> Self {
+        let widget = match spec.kind {
+            Kind::Text => Widget::Text(TextArea::with_text(&spec.default)),
+            Kind::Select | Kind::Checklist => Widget::Menu(Menu::new(
+                spec.options.clone(),
+                &spec.default,
+                spec.kind == Kind::Checklist,
+            )),
+            Kind::Pick => Widget::Pick(Picker::new(spec.options.clone(), &spec.default)),
+            Kind::Buttons => Widget::Buttons(Buttons::new(spec.options.clone(), &spec.default)),
+        };
+        Self {
+            x,
+            y,
+            width,
+            height: spec.height(),
+            name: spec.name,
+            echo: spec.echo,
+            placeholder: spec.placeholder,
+            validators: spec.validators,
+            widget,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Widget::Pick(picker) = &self.widget {
+            picker.check()?;
+        }
+        let value = self.widget.text();
+        self.validators
+            .iter()
+            .try_for_each(|validator| validator.check(&value))
+    }
+}
+
+pub enum Action {
+    Continue,
+    Submit,
+    Cancel,
+}
+
+/// The state of the prompt while fields are being filled in.
+pub struct Prompt {
+    pub inputs: Vec<Input>,
+    pub focus: usize,
+    pub error: Option<String>,
+    /// The key that leaves a multi-line field, where Enter starts a new line.
+    pub submit_key: KeyEvent,
+}
+
+impl Prompt {
+    pub fn new(inputs: Vec<Input>, submit_key: KeyEvent) -> Self {
+        Self {
+            inputs,
+            focus: 0,
+            error: None,
+            submit_key,
+        }
+    }
+
+    pub fn focused(&mut self) -> &mut Input {
+        &mut self.inputs[self.focus]
+    }
+
+    pub fn handle(&mut self, event: KeyEvent) -> Action {
+        let ctrl = event.modifiers.contains(KeyModifiers::CONTROL);
+        // Multi-line fields and lists use the arrow keys until the cursor reaches either end.
+        // Only text areas, where Enter starts a new line, are left with the submit key.
+        let input = self.focused();
+        let multiline = input.height > 1 || matches!(input.widget, Widget::Menu(_));
+        let text_area = input.height > 1 && matches!(input.widget, Widget::Text(_));
+        self.error = None;
+        // Buttons use the arrow keys and Tab, and are pressed with their first letter.
+        if let Widget::Buttons(buttons) = &mut self.focused().widget {
+            match event.code {
+                KeyCode::Char(c) if !ctrl && buttons.press(c) => return self.advance(),
+                _ if buttons.handle(event) => return Action::Continue,
+                _ => (),
+            }
+        }
+        match event.code {
+            _ if text_area && event == self.submit_key => return self.advance(),
+            KeyCode::Enter | KeyCode::Up | KeyCode::Down
+                if multiline && self.focused().widget.handle(event) => {}
+            KeyCode::Enter => return self.advance(),
+            KeyCode::Tab | KeyCode::Down => self.validate_next(),
+            KeyCode::BackTab | KeyCode::Up => self.previous(),
+            KeyCode::Char('s') if ctrl => return self.submit(),
+            KeyCode::Esc => return Action::Cancel,
+            KeyCode::Char('c') if ctrl => return Action::Cancel,
+            KeyCode::Char('d') if ctrl && self.focused().widget.is_empty() => {
+                return Action::Cancel
+            }
+            _ => {
+                self.focused().widget.handle(event);
+            }
+        }
+
+        Action::Continue
+    }
+
+    /// Moves to the next field, or submits the form from the last one.
+    fn advance(&mut self) -> Action {
+        if self.focus + 1 == self.inputs.len() {
+            return self.submit();
+        }
+        self.validate_next();
+        Action::Continue
+    }
+
+    /// Moves to the next field if the focused one is valid.
+    fn validate_next(&mut self) {
+        if let Err(e) = self.focused().validate() {
+            self.error = Some(e);
+        } else {
+            self.next();
+        }
+    }
+
+    /// Submits the form if every field is valid,
+    /// otherwise focuses the first invalid field.
+    fn submit(&mut self) -> Action {
+        for (i, input) in self.inputs.iter().enumerate() {
+            if let Err(e) = input.validate() {
+                self.focus = i;
+                self.error = Some(e);
+                return Action::Continue;
+            }
+        }
+
+        Action::Submit
+    }
+
+    fn next(&mut self) {
+        self.focus = (self.focus + 1) % self.inputs.len();
+    }
+
+    fn previous(&mut self) {
+        self.focus = (self.focus + self.inputs.len() - 1) % self.inputs.len();
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    field::{Choice, Echo, Field},
+    form::Line,
+    validate::Validator,
+};
+
+impl Line {
+    /// Parses a line written in the query syntax of the command line.
+    ///
+    /// Fields end in `?>` (or `*>` for masked fields and `#>` for multi-line fields
+    /// of `rows` rows), optionally followed by
+    /// `[default]`, `(placeholder)` and `{validator}` groups,
+    /// e.g. `Age: ?>[30](years){nonempty}{int:0..150}`.
+    /// Lists end in `@>` followed by two or more options separated by `|`, each written
+    /// as `value=Label` or just `value`, e.g. `Env: @>dev=Development|prod=Production`,
+    /// and checklists end in `%>` followed by one or more options in the same way.
+    /// Otherwise the line is a label, so that text like `50%>` can still be shown.
+    /// They may start with `name=` to give them an identifier other than their label,
+    /// e.g. `user=Username: ?>`.
+    pub fn parse(text: &str, mask: Echo, rows: u16) -> Result<Self, String> {
+        for (start, marker) in text.rmatch_indices(['?', '*', '#', '@', '%']) {
+            let rest = &text[start + marker.len()..];
+            let Some(rest) = rest.strip_prefix('>') else {
+                continue;
+            };
+
+            if marker == "@" || marker == "%" {
+                // Without options to choose from, such as in `50%>` or `user@>team`,
+                // the marker is part of the text.
+                let options: Vec<Choice> = rest.split('|').map(Choice::parse).collect();
+                if rest.is_empty() || (marker == "@" && options.len() < 2) {
+                    continue;
+                }
+                let (name, label) = split_name(&text[..start]);
+                let field = if marker == "@" {
+                    Field::select(options)
+                } else {
+                    Field::checklist(options)
+                };
+                let field = field.name(name.unwrap_or_default()).rows(rows);
+                return Ok(Self {
+                    label: label.to_owned(),
+                    field: Some(field),
+                });
+            }
+
+            let field = match marker {
+                "*" => Field::text().echo(mask),
+                "#" => Field::text().rows(rows),
+                _ => Field::text(),
+            };
+            if let Some(mut spec) = parse_groups(rest, field)? {
+                let (name, label) = split_name(&text[..start]);
+                spec.name = name.unwrap_or_default().to_owned();
+                return Ok(Self {
+                    label: label.to_owned(),
+                    field: Some(spec),
+                });
+            }
+        }
+
+        Ok(Self {
+            label: text.to_owned(),
+            field: None,
+        })
+    }
+}
+
+/// Derives a field name from its label, e.g. `Name` from `Name: `.
+pub(crate) fn label_name(label: &str) -> String {
+    label.trim().trim_end_matches(':').trim_end().to_owned()
+}
+
+fn parse_groups(mut rest: &str, mut spec: Field) -> Result<Option<Field>, String> {
+    while let Some(open) = rest.chars().next() {
+        let Some((content, remaining)) = split_group(rest, open) else {
+            return Ok(None);
+        };
+        match open {
+            '[' => spec.default = content.to_owned(),
+            '(' => spec.placeholder = content.to_owned(),
+            _ => spec.validators.push(Validator::parse(content)?),
+        }
+        rest = remaining;
+    }
+
+    Ok(Some(spec))
+}
+
+/// Splits an identifier followed by `=` off the front of a label.
+fn split_name(label: &str) -> (Option<&str>, &str) {
+    if let Some((name, rest)) = label.split_once('=') {
+        let mut chars = name.chars();
+        let valid = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if valid {
+            return (Some(name), rest);
+        }
+    }
+
+    (None, label)
+}
+
+/// Splits a group off the front of `text`, returning its content and the remaining text.
+/// Braces may nest so that regex quantifiers can be used in validators.
+fn split_group(text: &str, open: char) -> Option<(&str, &str)> {
+    match open {
+        '[' => text[1..].split_once(']'),
+        '(' => text[1..].split_once(')'),
+        '{' => {
+            let mut depth = 0;
+            for (i, c) in text.char_indices() {
+                match c {
+                    '{' => depth += 1,
+                    '}' if depth == 1 => return Some((&text[1..i], &text[i + 1..])),
+                    '}' => depth -= 1,
+                    _ => (),
+                }
+            }
+            None
+        }
+        _ => None,
+    }
+}
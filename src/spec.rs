@@ -0,0 +1,144 @@
+use std::{fs, io::Read, path::Path};
+
+use serde::Deserialize;
+
+use crate::{
+    border::Border,
+    field::{Choice, Echo, Field},
+    form::{Form, Line},
+    validate::Validator,
+};
+
+/// A form definition loaded with `--form`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FormSpec {
+    pub title: String,
+    pub border: Option<String>,
+    pub length: Option<u16>,
+    pub max_width: Option<u16>,
+    #[serde(default)]
+    pub lines: Vec<LineSpec>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LineSpec {
+    pub label: String,
+    #[serde(default, rename = "type")]
+    pub kind: LineKind,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub default: String,
+    #[serde(default)]
+    pub placeholder: String,
+    #[serde(default)]
+    pub validators: Vec<String>,
+    pub rows: Option<u16>,
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+#[derive(Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineKind {
+    #[default]
+    Label,
+    Text,
+    Password,
+    Textarea,
+    Select,
+    Checklist,
+}
+
+impl FormSpec {
+    /// Loads a form from a TOML or JSON file, or from stdin if `path` is `-`.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = if path == "-" {
+            let mut content = String::new();
+            std::io::stdin()
+                .read_to_string(&mut content)
+                .map_err(|e| format!("Could not read form from stdin: {}", e))?;
+            content
+        } else {
+            fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?
+        };
+
+        let json = match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some("json") => true,
+            Some("toml") => false,
+            _ => content.trim_start().starts_with('{'),
+        };
+
+        if json {
+            serde_json::from_str(&content).map_err(|e| format!("Invalid form: {}", e))
+        } else {
+            toml::from_str(&content).map_err(|e| format!("Invalid form: {}", e))
+        }
+    }
+
+    /// Whether any line is a field rather than a label.
+    pub fn has_fields(&self) -> bool {
+        self.lines.iter().any(|line| line.kind != LineKind::Label)
+    }
+
+    /// Builds the form, masking password fields with `mask` and giving textarea
+    /// and list fields `rows` rows unless they set their own.
+    pub fn into_form(self, mask: Echo, rows: u16) -> Result<Form, String> {
+        let mut form = Form::new(self.title);
+        if let Some(border) = &self.border {
+            form = form.border(Border::parse(border)?);
+        }
+        if let Some(length) = self.length {
+            form = form.length(length);
+        }
+        if let Some(max_width) = self.max_width {
+            form = form.max_width(max_width);
+        }
+        for line in self.lines {
+            form = form.line(line.into_line(mask, rows)?);
+        }
+        Ok(form)
+    }
+}
+
+impl LineSpec {
+    fn into_line(self, mask: Echo, rows: u16) -> Result<Line, String> {
+        let (echo, rows) = match self.kind {
+            LineKind::Label => {
+                return Ok(Line {
+                    label: self.label,
+                    field: None,
+                })
+            }
+            LineKind::Text => (Echo::Normal, 1),
+            LineKind::Password => (mask, 1),
+            LineKind::Textarea | LineKind::Select | LineKind::Checklist => {
+                (Echo::Normal, self.rows.unwrap_or(rows))
+            }
+        };
+
+        let field = match self.kind {
+            LineKind::Select | LineKind::Checklist if self.options.is_empty() => {
+                return Err(format!("No options given: {}", self.label))
+            }
+            LineKind::Select => Field::select(self.options.iter().map(|o| Choice::parse(o))),
+            LineKind::Checklist => Field::checklist(self.options.iter().map(|o| Choice::parse(o))),
+            _ => Field::text(),
+        };
+        let mut field = field
+            .name(self.name.unwrap_or_default())
+            .echo(echo)
+            .rows(rows)
+            .default(self.default)
+            .placeholder(self.placeholder);
+        for validator in &self.validators {
+            field = field.validator(Validator::parse(validator)?);
+        }
+
+        Ok(Line {
+            label: self.label,
+            field: Some(field),
+        })
+    }
+}
@@ -0,0 +1,108 @@
+use regex::Regex;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A check applied to a field's value.
+#[derive(Clone, Debug)]
+pub enum Validator {
+    NonEmpty,
+    Int(Option<i64>, Option<i64>),
+    MaxLength(usize),
+    Regex(Regex),
+}
+
+impl Validator {
+    /// Parses a validator such as `nonempty`, `int:1..10`, `max:20` or `re:^[a-z]+$`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (name, arg) = spec.split_once(':').unwrap_or((spec, ""));
+        match name {
+            "nonempty" => Ok(Self::NonEmpty),
+            "int" => {
+                let bound = |s: &str| -> Result<Option<i64>, String> {
+                    if s.is_empty() {
+                        return Ok(None);
+                    }
+                    s.parse()
+                        .map(Some)
+                        .map_err(|_| format!("Invalid integer bound: {}", s))
+                };
+                let (min, max) = arg.split_once("..").unwrap_or((arg, arg));
+                Ok(Self::Int(bound(min)?, bound(max)?))
+            }
+            "max" => arg
+                .parse()
+                .map(Self::MaxLength)
+                .map_err(|_| format!("Invalid maximum length: {}", arg)),
+            "re" => Regex::new(arg)
+                .map(Self::Regex)
+                .map_err(|e| format!("Invalid regex: {}", e)),
+            _ => Err(format!("Unknown validator: {}", name)),
+        }
+    }
+
+    /// Checks a value, returning a message describing the problem if it is invalid.
+    /// Empty values only fail `NonEmpty`, so that other validators keep a field optional.
+    pub fn check(&self, value: &str) -> Result<(), String> {
+        match self {
+            Self::NonEmpty if value.is_empty() => Err("This field is required".to_owned()),
+            _ if value.is_empty() => Ok(()),
+            Self::Int(min, max) => {
+                let n = value
+                    .parse::<i64>()
+                    .map_err(|_| "Must be an integer".to_owned())?;
+                match (min, max) {
+                    (Some(min), Some(max)) if n < *min || n > *max => {
+                        Err(format!("Must be between {} and {}", min, max))
+                    }
+                    (Some(min), None) if n < *min => Err(format!("Must be at least {}", min)),
+                    (None, Some(max)) if n > *max => Err(format!("Must be at most {}", max)),
+                    _ => Ok(()),
+                }
+            }
+            Self::MaxLength(max) if value.graphemes(true).count() > *max => {
+                Err(format!("Must be at most {} characters", max))
+            }
+            Self::Regex(re) if !re.is_match(value) => Err(format!("Must match {}", re)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(spec: &str, value: &str) -> Result<(), String> {
+        Validator::parse(spec).unwrap().check(value)
+    }
+
+    #[test]
+    fn only_nonempty_rejects_empty_values() {
+        assert!(check("nonempty", "").is_err());
+        for spec in ["int:0..150", "int:1..", "max:3", "re:^[a-z]+$"] {
+            assert_eq!(check(spec, ""), Ok(()), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn checks_integers() {
+        assert_eq!(check("int:0..150", "30"), Ok(()));
+        assert_eq!(
+            check("int:0..150", "x"),
+            Err("Must be an integer".to_owned())
+        );
+        assert_eq!(
+            check("int:0..150", "151"),
+            Err("Must be between 0 and 150".to_owned())
+        );
+        assert_eq!(check("int:1..", "0"), Err("Must be at least 1".to_owned()));
+        assert_eq!(check("int:..9", "10"), Err("Must be at most 9".to_owned()));
+    }
+
+    #[test]
+    fn checks_length_and_patterns() {
+        assert_eq!(check("max:3", "e\u{301}e\u{301}e\u{301}"), Ok(()));
+        assert!(check("max:3", "abcd").is_err());
+        assert_eq!(check("re:^[a-z]+$", "abc"), Ok(()));
+        assert!(check("re:^[a-z]+$", "ABC").is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use ibox::{
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    parse_key, Answers, Error, Field, Form, ScriptedEvents, TestBackend, Validator,
+};
+
+/// Fills in a form with fields A, B and C from a replay script.
+fn fill(script: &str) -> Result<Answers, Error> {
+    fill_form(
+        Form::new("Title")
+            .field("A: ", Field::text())
+            .field("B: ", Field::text())
+            .field("C: ", Field::text()),
+        script,
+    )
+}
+
+fn fill_form(form: Form, script: &str) -> Result<Answers, Error> {
+    let mut backend = TestBackend::new(30, 8);
+    let mut events = ScriptedEvents::parse(script).unwrap();
+    form.position(0, 0).run_with(&mut backend, &mut events)
+}
+
+fn answers(answers: &Answers) -> Vec<&str> {
+    answers
+        .iter()
+        .map(|(name, _)| answers.get(name).unwrap())
+        .collect()
+}
+
+#[test]
+fn parses_keys() {
+    let key = |code, modifiers| Some(KeyEvent::new(code, modifiers));
+    assert_eq!(parse_key("a"), key(KeyCode::Char('a'), KeyModifiers::NONE));
+    assert_eq!(
+        parse_key("Ctrl-W"),
+        key(KeyCode::Char('w'), KeyModifiers::CONTROL)
+    );
+    assert_eq!(
+        parse_key("Alt-Enter"),
+        key(KeyCode::Enter, KeyModifiers::ALT)
+    );
+    assert_eq!(
+        parse_key("Shift-Tab"),
+        key(KeyCode::BackTab, KeyModifiers::SHIFT)
+    );
+    assert_eq!(
+        parse_key("Space"),
+        key(KeyCode::Char(' '), KeyModifiers::NONE)
+    );
+    assert_eq!(parse_key("Nope"), None);
+    assert!(ScriptedEvents::parse("Ctrl-Nope").is_err());
+}
+
+#[test]
+fn edits_text() {
+    let script = "hello world\nCtrl-W\nthere\nCtrl-A\nDelete\nH\nEnd\nBackspace\nE\nEnter";
+    let answers = fill_form(Form::new("T").field("A: ", Field::text()), script).unwrap();
+    assert_eq!(answers.get("A"), Some("Hello therE"));
+}
+
+#[test]
+fn kills_and_moves_by_word() {
+    let script = "one two three\nAlt-B\nCtrl-K\nCtrl-Left\nCtrl-U\nEnter";
+    let answers = fill_form(Form::new("T").field("A: ", Field::text()), script).unwrap();
+    assert_eq!(answers.get("A"), Some("two "));
+}
+
+#[test]
+fn moves_between_fields() {
+    let answers = fill("a\nTab\nb\nTab\nc\nShift-Tab\nShift-Tab\nx\nCtrl-S").unwrap();
+    assert_eq!(self::answers(&answers), ["ax", "b", "c"]);
+
+    let answers = fill("Down\nDown\nc\nUp\nb\nBackTab\na\nCtrl-S").unwrap();
+    assert_eq!(self::answers(&answers), ["a", "b", "c"]);
+
+    let answers = fill("a\nEnter\nb\nEnter\nc\nEnter").unwrap();
+    assert_eq!(self::answers(&answers), ["a", "b", "c"]);
+}
+
+#[test]
+fn validates_before_moving_on() {
+    let form = Form::new("T").field("A: ", Field::text().validator(Validator::NonEmpty));
+    let answers = fill_form(form.clone(), "Enter\nTab\na\nEnter").unwrap();
+    assert_eq!(answers.get("A"), Some("a"));
+    // The script runs out while the empty field still refuses to submit.
+    assert!(matches!(fill_form(form, "Enter\nTab"), Err(Error::Io(_))));
+}
+
+#[test]
+fn cancels() {
+    for script in ["a\nEsc", "a\nCtrl-C", "Tab\nCtrl-D"] {
+        assert!(matches!(fill(script), Err(Error::Cancelled)), "{}", script);
+    }
+}
+
+#[test]
+fn ctrl_d_only_cancels_empty_fields() {
+    let answers = fill("a\nCtrl-D\nCtrl-S").unwrap();
+    assert_eq!(self::answers(&answers), ["a", "", ""]);
+}
+
+#[test]
+fn clears_the_box_when_cancelled() {
+    let mut backend = TestBackend::new(20, 4);
+    let mut events = ScriptedEvents::parse("a\nEsc").unwrap();
+    let result = Form::new("Title")
+        .field("A: ", Field::text())
+        .position(0, 0)
+        .run_with(&mut backend, &mut events);
+    assert!(matches!(result, Err(Error::Cancelled)));
+    assert!(backend.lines().iter().all(|line| line.is_empty()));
+    assert!(!backend.is_raw_mode());
+}
+
+#[test]
+fn leaves_text_areas_with_the_submit_key() {
+    let form = Form::new("T")
+        .field("A: ", Field::textarea())
+        .field("B: ", Field::text());
+    let answers = fill_form(form.clone(), "one\nEnter\ntwo\nAlt-Enter\nb\nEnter").unwrap();
+    assert_eq!(self::answers(&answers), ["one\ntwo", "b"]);
+
+    assert!(matches!(fill_form(form, "Ctrl-D"), Err(Error::Cancelled)));
+}
+
+#[test]
+fn lists_ignore_the_submit_key() {
+    let form = Form::new("T")
+        .field("A: ", Field::select(["x", "y"]))
+        .submit_key(parse_key("Ctrl-D").unwrap());
+    let answers = fill_form(form, "Ctrl-D\nDown\nEnter").unwrap();
+    assert_eq!(answers.get("A"), Some("y"));
+}
+
+#[test]
+fn edits_combining_marks_as_one_character() {
+    let form = Form::new("T").field("A: ", Field::text());
+    // Left moves over b, then over e and its accent together.
+    let answers = fill_form(form.clone(), "ae\u{301}b\nLeft\nLeft\nBackspace\nEnter").unwrap();
+    assert_eq!(answers.get("A"), Some("e\u{301}b"));
+
+    let answers = fill_form(form.clone(), "ae\u{301}\nBackspace\nEnter").unwrap();
+    assert_eq!(answers.get("A"), Some("a"));
+
+    let answers = fill_form(form, "ae\u{301}b\nHome\nDelete\nDelete\nEnter").unwrap();
+    assert_eq!(answers.get("A"), Some("b"));
+}
+
+#[test]
+fn scrolls_fields_of_wide_characters() {
+    let draw = |script: &str| {
+        let mut backend = TestBackend::new(20, 4);
+        let mut events = ScriptedEvents::parse(script).unwrap();
+        // The script runs out with the cursor still in the field.
+        let result = Form::new("T")
+            .field("Q: ", Field::text())
+            .length(6)
+            .position(0, 0)
+            .run_with(&mut backend, &mut events);
+        assert!(matches!(result, Err(Error::Io(_))));
+        (backend.lines()[1].clone(), backend.cursor())
+    };
+
+    // Seven columns fit three wide characters, scrolled to keep the cursor visible.
+    assert_eq!(draw("一二三四五六"), ("│Q: 四五六 │".to_owned(), (10, 1)));
+    assert_eq!(
+        draw("一二三四五六\nLeft"),
+        ("│Q: 四五六 │".to_owned(), (8, 1))
+    );
+    assert_eq!(
+        draw("一二三四五六\nHome"),
+        ("│Q: 一二三 │".to_owned(), (4, 1))
+    );
+}
@@ -0,0 +1,52 @@
+use ibox::{Answers, Echo, Error, Form, Line, ScriptedEvents, TestBackend};
+
+/// Fills in a box made of the given query lines from a replay script.
+fn fill(lines: &[&str], script: &str) -> Result<Answers, Error> {
+    let form = lines.iter().fold(Form::new("Title"), |form, line| {
+        form.line(Line::parse(line, Echo::Mask('*'), 4).unwrap())
+    });
+    let mut backend = TestBackend::new(40, 10);
+    let mut events = ScriptedEvents::parse(script).unwrap();
+    form.position(0, 0).run_with(&mut backend, &mut events)
+}
+
+#[test]
+fn parses_fields() {
+    let answers = fill(
+        &[
+            "Name: ?>[alice]",
+            "user=Pass: *>",
+            "Env: @>dev|prod",
+            "Extras: %>docs",
+        ],
+        "Enter\nx\nEnter\nDown\nEnter\nSpace\nEnter",
+    )
+    .unwrap();
+    assert_eq!(answers.get("Name"), Some("alice"));
+    assert_eq!(answers.get("user"), Some("x"));
+    assert_eq!(answers.get("Env"), Some("prod"));
+    assert_eq!(answers.len(), 4);
+}
+
+#[test]
+fn markers_without_options_are_text() {
+    // Labels need no input, so the box is drawn without reading any keys.
+    for line in [
+        "Progress 50%>",
+        "Pick one: @>",
+        "mail user@>team",
+        "Why?> because",
+    ] {
+        let answers = fill(&[line], "").unwrap();
+        assert!(answers.is_empty(), "{}", line);
+    }
+}
+
+#[test]
+fn markers_before_a_field_are_part_of_its_label() {
+    let answers = fill(&["mail user@>team to: ?>"], "x\nEnter").unwrap();
+    assert_eq!(answers.get("mail user@>team to"), Some("x"));
+
+    let answers = fill(&["At 50%> now: ?>"], "x\nEnter").unwrap();
+    assert_eq!(answers.get("At 50%> now"), Some("x"));
+}
@@ -0,0 +1,256 @@
+use ibox::{Backend, Border, Error, Field, Form, ScriptedEvents, Style, TestBackend};
+use unicode_width::UnicodeWidthStr;
+
+/// Draws a box of labels on a terminal of the given size and returns its rows.
+fn draw(form: Form, width: u16, height: u16) -> Vec<String> {
+    let mut backend = TestBackend::new(width, height);
+    form.run_with(&mut backend, &mut ScriptedEvents::default())
+        .unwrap();
+    backend.lines()
+}
+
+fn form() -> Form {
+    Form::new("Title").label("Hello").label("Wide world")
+}
+
+fn bordered(border: Border) -> Vec<String> {
+    draw(form().border(border).length(2).position(0, 0), 20, 5)
+}
+
+#[test]
+fn single_border() {
+    assert_eq!(
+        bordered(Border::SINGLE),
+        [
+            "┌─Title───────┐",
+            "│Hello        │",
+            "│Wide world   │",
+            "└─────────────┘",
+            "",
+        ]
+    );
+}
+
+#[test]
+fn double_border() {
+    assert_eq!(
+        bordered(Border::DOUBLE),
+        [
+            "╔═Title═══════╗",
+            "║Hello        ║",
+            "║Wide world   ║",
+            "╚═════════════╝",
+            "",
+        ]
+    );
+}
+
+#[test]
+fn thick_border() {
+    assert_eq!(
+        bordered(Border::THICK),
+        [
+            "┏━Title━━━━━━━┓",
+            "┃Hello        ┃",
+            "┃Wide world   ┃",
+            "┗━━━━━━━━━━━━━┛",
+            "",
+        ]
+    );
+}
+
+#[test]
+fn curved_border() {
+    assert_eq!(
+        bordered(Border::CURVED),
+        [
+            "╭─Title───────╮",
+            "│Hello        │",
+            "│Wide world   │",
+            "╰─────────────╯",
+            "",
+        ]
+    );
+}
+
+#[test]
+fn custom_border() {
+    assert_eq!(
+        bordered(Border(['+', '-', '+', '|', '+', '+'])),
+        [
+            "+-Title-------+",
+            "|Hello        |",
+            "|Wide world   |",
+            "+-------------+",
+            "",
+        ]
+    );
+}
+
+#[test]
+fn center() {
+    assert_eq!(
+        draw(form().length(2).center(true), 24, 6),
+        [
+            "",
+            "    ┌─Title───────┐",
+            "    │Hello        │",
+            "    │Wide world   │",
+            "    └─────────────┘",
+            "",
+        ]
+    );
+}
+
+#[test]
+fn stretch() {
+    assert_eq!(
+        draw(form().length(2).stretch(true).position(3, 1), 20, 6),
+        [
+            "",
+            "┌─Title────────────┐",
+            "│Hello             │",
+            "│Wide world        │",
+            "└──────────────────┘",
+            "",
+        ]
+    );
+}
+
+#[test]
+fn position() {
+    assert_eq!(
+        draw(form().length(2).position(4, 1), 20, 6),
+        [
+            "",
+            "    ┌─Title───────┐",
+            "    │Hello        │",
+            "    │Wide world   │",
+            "    └─────────────┘",
+            "",
+        ]
+    );
+}
+
+#[test]
+fn max_width() {
+    assert_eq!(
+        draw(form().max_width(9).position(0, 0), 20, 6),
+        [
+            "┌─Title─┐",
+            "│Hello  │",
+            "│Wide   │",
+            "│world  │",
+            "└───────┘",
+            "",
+        ]
+    );
+}
+
+#[test]
+fn length() {
+    assert_eq!(
+        draw(form().length(6).position(0, 0), 20, 5),
+        [
+            "┌─Title───────────┐",
+            "│Hello            │",
+            "│Wide world       │",
+            "└─────────────────┘",
+            "",
+        ]
+    );
+}
+
+#[test]
+fn clips_at_the_right_edge() {
+    let mut backend = TestBackend::new(3, 1);
+    backend.print("abc\u{200b}", Style::Plain).unwrap();
+    assert_eq!(backend.lines(), ["abc"]);
+
+    // Nothing spills over into the next row either.
+    let mut backend = TestBackend::new(3, 2);
+    backend.move_to(0, 1).unwrap();
+    backend.print("xyz", Style::Plain).unwrap();
+    backend.move_to(0, 0).unwrap();
+    backend.print("abc\u{200b}", Style::Plain).unwrap();
+    assert_eq!(backend.lines(), ["abc", "xyz"]);
+}
+
+#[test]
+fn huge_boxes_do_not_overflow() {
+    // A terminal of unknown size, like one whose size cannot be queried.
+    let mut backend = TestBackend::new(0, 0);
+    let mut events = ScriptedEvents::parse("Enter").unwrap();
+    let answers = Form::new("T")
+        .field("Q: ", Field::text())
+        .length(u16::MAX)
+        .position(0, 0)
+        .run_with(&mut backend, &mut events)
+        .unwrap();
+    assert_eq!(answers.get("Q"), Some(""));
+
+    for (x, y) in [(u16::MAX, 0), (0, u16::MAX), (u16::MAX - 2, u16::MAX - 3)] {
+        let result = Form::new("T")
+            .field("Q: ", Field::text())
+            .position(x, y)
+            .run_with(&mut TestBackend::new(0, 0), &mut ScriptedEvents::default());
+        assert!(
+            matches!(result, Err(Error::TooSmall { .. })),
+            "{:?}",
+            (x, y)
+        );
+    }
+}
+
+#[test]
+fn wide_labels_line_up_the_border() {
+    let lines = draw(
+        Form::new("タイトル")
+            .label("日本語のラベル")
+            .label("emoji 🎉🎉")
+            .length(2)
+            .position(0, 0),
+        30,
+        5,
+    );
+    assert_eq!(
+        lines,
+        [
+            "┌─タイトル────────┐",
+            "│日本語のラベル   │",
+            "│emoji 🎉🎉       │",
+            "└─────────────────┘",
+            "",
+        ]
+    );
+    for line in &lines[..4] {
+        assert_eq!(line.width(), 19, "{}", line);
+    }
+}
+
+#[test]
+fn very_wide_choices_do_not_overflow() {
+    let wide = "x".repeat(usize::from(u16::MAX) + 1);
+    let mut backend = TestBackend::new(40, 16);
+    let mut events = ScriptedEvents::parse("Enter").unwrap();
+    let answers = Form::new("Open")
+        .field("> ", Field::pick([wide.as_str(), "y"]).name("file"))
+        .position(0, 0)
+        .run_with(&mut backend, &mut events)
+        .unwrap();
+    assert_eq!(answers.get("file"), Some(wide.as_str()));
+    assert_eq!(backend.lines()[2].chars().count(), 40);
+}
+
+#[test]
+fn very_wide_buttons_do_not_overflow() {
+    let wide = "x".repeat(usize::from(u16::MAX));
+    let mut backend = TestBackend::new(40, 4);
+    let mut events = ScriptedEvents::parse("Enter").unwrap();
+    let answers = Form::new("Deploy?")
+        .field("", Field::buttons([wide.as_str(), wide.as_str()]).name("b"))
+        .position(0, 0)
+        .run_with(&mut backend, &mut events)
+        .unwrap();
+    assert_eq!(answers.get("b"), Some(wide.as_str()));
+}